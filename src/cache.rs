@@ -0,0 +1,380 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Opt-in in-memory cache for responses from read-only endpoints.
+//!
+//! The cache is disabled by default. Enable it with `Client::with_cache`:
+//!
+//! ```
+//! # use wallabag_api::types::Config;
+//! # use wallabag_api::cache::CacheConfig;
+//! # use wallabag_api::Client;
+//! # use std::time::Duration;
+//! # let config = Config {
+//! #     client_id: "id".to_owned(),
+//! #     client_secret: "secret".to_owned(),
+//! #     username: "user".to_owned(),
+//! #     password: "pass".to_owned(),
+//! #     base_url: "https://example.com".to_owned(),
+//! # };
+//! let client = Client::new(config).with_cache(CacheConfig {
+//!     tags_ttl: Some(Duration::from_secs(60)),
+//!     entry_ttl: None,
+//!     ..Default::default()
+//! });
+//! ```
+//!
+//! Methods on the client that modify data on the server automatically invalidate the cached
+//! responses they could have made stale.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::types::EntryId;
+use crate::utils::EndPoint;
+
+/// Configuration for the response cache. Each `*_ttl` field sets how long a response from that
+/// endpoint is kept. Set a field to `None` to never cache that endpoint.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// Time to live for the list of all tags (`get_tags`).
+    pub tags_ttl: Option<Duration>,
+
+    /// Time to live for the current user information (`get_user`).
+    pub user_ttl: Option<Duration>,
+
+    /// Time to live for the API version (`get_api_version`).
+    pub version_ttl: Option<Duration>,
+
+    /// Time to live for single entries (`get_entry`).
+    pub entry_ttl: Option<Duration>,
+
+    /// Time to live for the tags of an entry (`get_tags_for_entry`).
+    pub entry_tags_ttl: Option<Duration>,
+
+    /// Time to live for the annotations of an entry (`get_annotations`).
+    pub annotations_ttl: Option<Duration>,
+
+    /// Maximum number of responses kept at once. When full, expired responses are dropped first,
+    /// then the oldest response.
+    pub max_entries: usize,
+}
+
+/// The defaults cache the rarely changing endpoints for a long time and entries for a short
+/// time.
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            tags_ttl: Some(Duration::from_secs(5 * 60)),
+            user_ttl: Some(Duration::from_secs(60 * 60)),
+            version_ttl: Some(Duration::from_secs(60 * 60)),
+            entry_ttl: Some(Duration::from_secs(60)),
+            entry_tags_ttl: Some(Duration::from_secs(60)),
+            annotations_ttl: Some(Duration::from_secs(60)),
+            max_entries: 256,
+        }
+    }
+}
+
+/// A response body as stored in the cache.
+#[derive(Debug)]
+struct CachedResponse {
+    body: String,
+    inserted_at: Instant,
+    expires_at: Instant,
+}
+
+/// Stores raw response bodies keyed by the endpoint they were retrieved from.
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    config: CacheConfig,
+    responses: HashMap<EndPoint, CachedResponse>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Time to live for responses from an endpoint, or `None` if the endpoint isn't cached.
+    fn ttl(&self, end_point: EndPoint) -> Option<Duration> {
+        match end_point {
+            EndPoint::Tags => self.config.tags_ttl,
+            EndPoint::User => self.config.user_ttl,
+            EndPoint::Version => self.config.version_ttl,
+            EndPoint::Entry(_) => self.config.entry_ttl,
+            EndPoint::EntryTags(_) => self.config.entry_tags_ttl,
+            EndPoint::Annotations(_) => self.config.annotations_ttl,
+            _ => None,
+        }
+    }
+
+    /// Whether responses from this endpoint are cached at all.
+    pub(crate) fn is_cached(&self, end_point: EndPoint) -> bool {
+        self.ttl(end_point).is_some()
+    }
+
+    /// Get the cached response body for an endpoint, if present and not expired.
+    pub(crate) fn get(&mut self, end_point: EndPoint) -> Option<&str> {
+        self.get_at(end_point, Instant::now())
+    }
+
+    fn get_at(&mut self, end_point: EndPoint, now: Instant) -> Option<&str> {
+        let expired = self
+            .responses
+            .get(&end_point)
+            .map(|response| response.expires_at <= now)?;
+        if expired {
+            self.responses.remove(&end_point);
+            None
+        } else {
+            self.responses
+                .get(&end_point)
+                .map(|response| response.body.as_str())
+        }
+    }
+
+    /// Store a response body. Does nothing if the endpoint isn't cached.
+    pub(crate) fn insert(&mut self, end_point: EndPoint, body: String) {
+        self.insert_at(end_point, body, Instant::now())
+    }
+
+    fn insert_at(&mut self, end_point: EndPoint, body: String, now: Instant) {
+        let ttl = match self.ttl(end_point) {
+            Some(ttl) => ttl,
+            None => return,
+        };
+        if self.config.max_entries == 0 {
+            return;
+        }
+
+        if !self.responses.contains_key(&end_point)
+            && self.responses.len() >= self.config.max_entries
+        {
+            self.responses
+                .retain(|_, response| response.expires_at > now);
+        }
+        while !self.responses.contains_key(&end_point)
+            && self.responses.len() >= self.config.max_entries
+        {
+            let oldest = self
+                .responses
+                .iter()
+                .min_by_key(|(_, response)| response.inserted_at)
+                .map(|(end_point, _)| *end_point);
+            match oldest {
+                Some(oldest) => self.responses.remove(&oldest),
+                None => break,
+            };
+        }
+
+        self.responses.insert(
+            end_point,
+            CachedResponse {
+                body,
+                inserted_at: now,
+                expires_at: now + ttl,
+            },
+        );
+    }
+
+    /// Drop the cached response for a single endpoint.
+    pub(crate) fn invalidate(&mut self, end_point: EndPoint) {
+        self.responses.remove(&end_point);
+    }
+
+    /// Drop all cached responses that relate to a single entry.
+    pub(crate) fn invalidate_entry(&mut self, id: EntryId) {
+        self.invalidate(EndPoint::Entry(id));
+        self.invalidate(EndPoint::EntryTags(id));
+        self.invalidate(EndPoint::Annotations(id));
+    }
+
+    /// Drop all cached entries and their tags. Used when a change affects an unknown set of
+    /// entries, eg. deleting a tag from every entry.
+    pub(crate) fn invalidate_all_entries(&mut self) {
+        self.responses.retain(|end_point, _| {
+            !matches!(end_point, EndPoint::Entry(_) | EndPoint::EntryTags(_))
+        });
+    }
+
+    /// Drop all cached annotations, including those embedded in cached entries. Annotation
+    /// endpoints are keyed by annotation id for modifications, so the affected entry is unknown.
+    pub(crate) fn invalidate_all_annotations(&mut self) {
+        self.responses.retain(|end_point, _| {
+            !matches!(end_point, EndPoint::Entry(_) | EndPoint::Annotations(_))
+        });
+    }
+
+    /// Drop all cached responses.
+    pub(crate) fn clear(&mut self) {
+        self.responses.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use crate::types::{NewEntry, PatchEntry};
+    use async_std::task::block_on;
+
+    #[test]
+    fn test_cache_expires_after_ttl() {
+        let mut cache = ResponseCache::new(CacheConfig {
+            tags_ttl: Some(Duration::from_secs(10)),
+            ..Default::default()
+        });
+        let now = Instant::now();
+        cache.insert_at(EndPoint::Tags, "[]".to_owned(), now);
+
+        assert_eq!(
+            cache.get_at(EndPoint::Tags, now + Duration::from_secs(5)),
+            Some("[]")
+        );
+        assert_eq!(
+            cache.get_at(EndPoint::Tags, now + Duration::from_secs(10)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cache_skips_disabled_endpoints() {
+        let mut cache = ResponseCache::new(CacheConfig {
+            user_ttl: None,
+            ..Default::default()
+        });
+        cache.insert(EndPoint::User, "{}".to_owned());
+        cache.insert(EndPoint::Entries, "{}".to_owned());
+
+        assert_eq!(cache.get(EndPoint::User), None);
+        assert_eq!(cache.get(EndPoint::Entries), None);
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest_when_full() {
+        let mut cache = ResponseCache::new(CacheConfig {
+            max_entries: 2,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        cache.insert_at(EndPoint::Entry(EntryId(1)), "1".to_owned(), now);
+        cache.insert_at(
+            EndPoint::Entry(EntryId(2)),
+            "2".to_owned(),
+            now + Duration::from_secs(1),
+        );
+        cache.insert_at(
+            EndPoint::Entry(EntryId(3)),
+            "3".to_owned(),
+            now + Duration::from_secs(2),
+        );
+
+        let later = now + Duration::from_secs(3);
+        assert_eq!(cache.get_at(EndPoint::Entry(EntryId(1)), later), None);
+        assert_eq!(cache.get_at(EndPoint::Entry(EntryId(2)), later), Some("2"));
+        assert_eq!(cache.get_at(EndPoint::Entry(EntryId(3)), later), Some("3"));
+    }
+
+    #[test]
+    fn test_cache_invalidate_entry() {
+        let mut cache = ResponseCache::new(CacheConfig::default());
+        cache.insert(EndPoint::Entry(EntryId(1)), "1".to_owned());
+        cache.insert(EndPoint::EntryTags(EntryId(1)), "[]".to_owned());
+        cache.insert(EndPoint::Entry(EntryId(2)), "2".to_owned());
+
+        cache.invalidate_entry(EntryId(1));
+
+        assert_eq!(cache.get(EndPoint::Entry(EntryId(1))), None);
+        assert_eq!(cache.get(EndPoint::EntryTags(EntryId(1))), None);
+        assert_eq!(cache.get(EndPoint::Entry(EntryId(2))), Some("2"));
+    }
+
+    #[test]
+    fn test_cached_responses_skip_requests() {
+        block_on(async {
+            let server = MockServer::new();
+            let entry = server.add_entry("https://example.com/", &[]);
+            let mut client = server.client().with_cache(CacheConfig::default());
+
+            client.get_tags().await.unwrap();
+            client.get_tags().await.unwrap();
+            client.get_entry(entry.id).await.unwrap();
+            client
+                .update_entry(entry.id, &PatchEntry::default())
+                .await
+                .unwrap();
+            client.get_entry(entry.id).await.unwrap();
+
+            assert_eq!(
+                server.requests(),
+                vec![
+                    "POST /oauth/v2/token",
+                    "GET /api/tags.json",
+                    "GET /api/entries/1.json",
+                    "PATCH /api/entries/1.json",
+                    "GET /api/entries/1.json",
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_create_entry_invalidates_cache() {
+        block_on(async {
+            let server = MockServer::new();
+            let entry = server.add_entry("https://example.com/", &[]);
+            let mut client = server.client().with_cache(CacheConfig::default());
+
+            client.get_tags().await.unwrap();
+            client.get_entry(entry.id).await.unwrap();
+            let mut new_entry = NewEntry::new_with_url("https://example.com/".to_owned());
+            new_entry.tags = Some(vec!["new".to_owned()]);
+            client.create_entry(&new_entry).await.unwrap();
+            client.get_tags().await.unwrap();
+            client.get_entry(entry.id).await.unwrap();
+
+            assert_eq!(
+                server.requests(),
+                vec![
+                    "POST /oauth/v2/token",
+                    "GET /api/tags.json",
+                    "GET /api/entries/1.json",
+                    "POST /api/entries.json",
+                    "GET /api/tags.json",
+                    "GET /api/entries/1.json",
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_delete_tag_invalidates_cache() {
+        block_on(async {
+            let server = MockServer::new();
+            let entry = server.add_entry("https://example.com/", &["rust"]);
+            let mut client = server.client().with_cache(CacheConfig::default());
+
+            client.get_tags().await.unwrap();
+            client.get_entry(entry.id).await.unwrap();
+            client.delete_tag(entry.tags[0].id).await.unwrap();
+            assert!(client.get_tags().await.unwrap().is_empty());
+            assert!(client.get_entry(entry.id).await.unwrap().tags.is_empty());
+
+            assert_eq!(
+                server.requests(),
+                vec![
+                    "POST /oauth/v2/token",
+                    "GET /api/tags.json",
+                    "GET /api/entries/1.json",
+                    "DELETE /api/tags/1.json",
+                    "GET /api/tags.json",
+                    "GET /api/entries/1.json",
+                ]
+            );
+        })
+    }
+}
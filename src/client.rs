@@ -6,8 +6,12 @@
 
 // std libs
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 // extern crates
+use futures_util::future::try_join_all;
+use futures_util::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use log::{debug, max_level, trace, LevelFilter};
 use serde::de::DeserializeOwned;
 use serde::ser::Serialize;
@@ -17,16 +21,113 @@ use surf::{http::StatusCode, Body};
 use surf::{Request, Response};
 
 // local imports
+use crate::cache::{CacheConfig, ResponseCache};
 use crate::errors::{
-    ClientError, ClientResult, CodeMessage, ResponseCodeMessageError, ResponseError,
+    ApiError, ClientError, ClientResult, ExportError, RequestContext, ResponseError,
 };
+use crate::export::{
+    csv_row, decode, export_category, json_record, BulkExport, ExportProgress, ExportSource,
+    ExportedEntry, CSV_HEADER,
+};
+use crate::metrics::{Metrics, MetricsHandle, RequestMetrics, StatusClass};
+use crate::middleware::{Middleware, MiddlewareStack};
 use crate::types::{
-    Annotation, AnnotationRows, Annotations, Config, DeletedEntry, DeletedTag, Entries,
-    EntriesExistParams, EntriesFilter, EntriesPage, Entry, ExistsInfo, ExistsResponse, Format,
-    NewAnnotation, NewEntry, NewlyRegisteredInfo, PaginatedEntries, PatchEntry, RegisterInfo,
-    RequestEntriesFilter, Tag, TagString, Tags, TokenInfo, User, ID, UNIT,
+    Annotation, AnnotationId, AnnotationRows, Annotations, Config, DeletedEntry, DeletedTag,
+    Entries, EntriesExistParams, EntriesFilter, EntriesPage, Entry, EntryId, ExistsInfo,
+    ExistsResponse, Format, NewAnnotation, NewEntry, NewlyRegisteredInfo, PaginatedEntries,
+    PatchEntry, RegisterInfo, RequestEntriesFilter, Tag, TagId, TagString, Tags, TokenInfo, User,
+    UNIT,
 };
+use crate::utils::redact::loggable_body;
+use crate::utils::telemetry::ApiCall;
 use crate::utils::{EndPoint, UrlBuilder};
+use crate::validate::{validate_tags, Validate};
+
+/// Size of the chunks in which exports are written by `Client::export_entry_to`.
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The context of errors of a request to the endpoint.
+fn request_context(method: Method, end_point: EndPoint) -> RequestContext {
+    RequestContext {
+        method,
+        endpoint: end_point.name(),
+    }
+}
+
+/// Read the body of a successful response. Failing to read it is a `Transport` error, since the
+/// connection failed; a body that isn't UTF-8 is a `Decode` error, which isn't worth retrying.
+async fn read_text(mut response: Response, context: RequestContext) -> ClientResult<String> {
+    let bytes = response
+        .body_bytes()
+        .await
+        .map_err(|error| ClientError::Transport { context, error })?;
+    String::from_utf8(bytes).map_err(|error| ClientError::Decode {
+        context,
+        error: error.into(),
+    })
+}
+
+/// Decode a JSON response body. A body that doesn't match the expected structure becomes a
+/// `Decode` error, which isn't worth retrying.
+fn decode_json<T: DeserializeOwned>(text: &str) -> Result<T, serde_json::Error> {
+    match serde_json::from_str(text) {
+        Ok(json) => {
+            if max_level() >= LevelFilter::Debug {
+                debug!("Deserialized json response body: {}", loggable_body(text));
+            }
+            Ok(json)
+        }
+        Err(e) => {
+            if max_level() >= LevelFilter::Debug {
+                debug!("Deserialize json failed for: {}", loggable_body(text));
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Write an export response to `writer`, after checking that it is in the expected format.
+/// Returns the number of bytes written.
+async fn write_export<W, F>(
+    mut response: Response,
+    fmt: Format,
+    writer: &mut W,
+    progress: &mut F,
+) -> ClientResult<u64>
+where
+    W: AsyncWrite + Unpin + Send,
+    F: FnMut(ExportProgress) + Send,
+{
+    let content_type = response
+        .content_type()
+        .map(|mime| mime.essence().to_owned());
+    if !content_type
+        .as_deref()
+        .is_some_and(|essence| fmt.content_types().contains(&essence))
+    {
+        return Err(ExportError::ContentType {
+            format: fmt,
+            content_type,
+        }
+        .into());
+    }
+
+    let total = response.len().map(|len| len as u64);
+    let mut body = response.take_body();
+    let mut buffer = vec![0; EXPORT_CHUNK_SIZE];
+    let mut bytes = 0;
+    loop {
+        let read = body.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read]).await?;
+        bytes += read as u64;
+        progress(ExportProgress { bytes, total });
+    }
+    writer.flush().await?;
+    Ok(bytes)
+}
 
 /// The main thing that provides all the methods for interacting with the
 /// Wallabag API.
@@ -38,6 +139,10 @@ pub struct Client {
     password: String,
     token_info: Option<TokenInfo>,
     url_base: UrlBuilder,
+    cache: Option<ResponseCache>,
+    http: surf::Client,
+    middleware: MiddlewareStack,
+    metrics: MetricsHandle,
 }
 
 impl Client {
@@ -50,9 +155,63 @@ impl Client {
             password: config.password,
             token_info: None,
             url_base: UrlBuilder::new(config.base_url),
+            cache: None,
+            http: surf::Client::new(),
+            middleware: MiddlewareStack::default(),
+            metrics: MetricsHandle::default(),
+        }
+    }
+
+    /// Use a custom surf client for sending requests. Useful for configuring timeouts, or for
+    /// sending requests to a fake server in tests (see the `testing` module).
+    pub fn with_surf_client(mut self, http: surf::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Enable the in-memory response cache for read-only endpoints. See the `cache` module for
+    /// more information.
+    pub fn with_cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(ResponseCache::new(config));
+        self
+    }
+
+    /// Add a middleware layer around every request sent by this client, including token requests.
+    /// Layers run in the order they are added. See the `middleware` module for more information.
+    pub fn with_middleware<M: Middleware>(mut self, middleware: M) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Report metrics about every request sent by this client. See the `metrics` module for more
+    /// information.
+    pub fn with_metrics<M: Metrics>(mut self, metrics: Arc<M>) -> Self {
+        self.metrics = MetricsHandle::new(metrics);
+        self
+    }
+
+    /// Drop all responses stored in the cache. Does nothing if the cache isn't enabled.
+    pub fn clear_cache(&mut self) {
+        if let Some(ref mut cache) = self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Run `f` on the cache if it is enabled.
+    fn update_cache<F: FnOnce(&mut ResponseCache)>(&mut self, f: F) {
+        if let Some(ref mut cache) = self.cache {
+            f(cache);
         }
     }
 
+    /// Send a request to a url outside of the API, like a preview picture. Neither the access
+    /// token nor the middleware of the client are applied, since those may add credentials that
+    /// are meant for the wallabag server only.
+    #[cfg(feature = "epub")]
+    pub(crate) async fn send_external(&self, request: Request) -> surf::Result<Response> {
+        self.http.send(request).await
+    }
+
     /// Internal method to get a valid access token. If no access token loaded
     /// yet, then get a new one.
     async fn get_token(&mut self) -> ClientResult<String> {
@@ -87,6 +246,7 @@ impl Client {
         if self.token_info.is_none() {
             return self.load_token().await;
         }
+        self.metrics.record_token_refresh();
 
         let mut fields = HashMap::new();
         fields.insert("grant_type".to_owned(), "refresh_token".to_owned());
@@ -118,11 +278,31 @@ impl Client {
         J: Serialize,
         Q: Serialize,
     {
-        Ok(self
-            .smart_q(method, end_point, query, json)
+        let response = self.smart_q(method, end_point, query, json).await?;
+        read_text(response, request_context(method, end_point)).await
+    }
+
+    /// Smartly run a request that expects to receive binary data back. Handles adding
+    /// authorization headers, and retry on expired token.
+    async fn smart_bytes_q<J, Q>(
+        &mut self,
+        method: Method,
+        end_point: EndPoint,
+        query: &Q,
+        json: &J,
+    ) -> ClientResult<Vec<u8>>
+    where
+        J: Serialize,
+        Q: Serialize,
+    {
+        self.smart_q(method, end_point, query, json)
             .await?
-            .body_string()
-            .await?)
+            .body_bytes()
+            .await
+            .map_err(|error| ClientError::Transport {
+                context: request_context(method, end_point),
+                error,
+            })
     }
 
     /// Smartly run a request that expects to receive json back. Handles adding
@@ -139,29 +319,45 @@ impl Client {
         J: Serialize,
         Q: Serialize,
     {
-        if max_level() >= LevelFilter::Debug {
-            let text = self
-                .smart_q(method, end_point, query, json)
-                .await?
-                .body_string()
-                .await?;
-            match serde_json::from_str(&text) {
-                Ok(j) => {
-                    debug!("Deserialized json response body: {}", &text);
-                    Ok(j)
-                }
-                Err(e) => {
-                    debug!("Deserialize json failed for: {}", &text);
-                    Err(ClientError::SerdeJsonError(e))
-                }
-            }
-        } else {
-            Ok(self
-                .smart_q(method, end_point, query, json)
-                .await?
-                .body_json()
-                .await?)
+        let text = self.smart_text_q(method, end_point, query, json).await?;
+        decode_json(&text).map_err(|error| ClientError::Decode {
+            context: request_context(method, end_point),
+            error: error.into(),
+        })
+    }
+
+    /// Run a GET request that expects to receive json back, using the response cache if it is
+    /// enabled for this endpoint.
+    async fn cached_json_q<T>(&mut self, end_point: EndPoint) -> ClientResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let cached = match self.cache {
+            Some(ref cache) => cache.is_cached(end_point),
+            None => false,
+        };
+        if !cached {
+            return self.smart_json_q(Method::Get, end_point, UNIT, UNIT).await;
         }
+
+        let context = request_context(Method::Get, end_point);
+        if let Some(text) = self.cache.as_mut().and_then(|cache| cache.get(end_point)) {
+            trace!("Using cached response for {}", end_point);
+            return serde_json::from_str(text).map_err(|error| ClientError::Decode {
+                context,
+                error: error.into(),
+            });
+        }
+
+        let text = self
+            .smart_text_q(Method::Get, end_point, UNIT, UNIT)
+            .await?;
+        let json = decode_json(&text).map_err(|error| ClientError::Decode {
+            context,
+            error: error.into(),
+        })?;
+        self.update_cache(|cache| cache.insert(end_point, text));
+        Ok(json)
     }
 
     /// Smartly run a request that expects to receive json back. Handles adding
@@ -173,6 +369,27 @@ impl Client {
         query: &Q,
         json: &J,
     ) -> ClientResult<Response>
+    where
+        J: Serialize,
+        Q: Serialize,
+    {
+        let call = ApiCall::start(method, end_point);
+        let result = call
+            .instrument(self.smart_q_with_retry(method, end_point, query, json, &call))
+            .await;
+        call.finish(&result);
+        result
+    }
+
+    /// Does the work for `smart_q`.
+    async fn smart_q_with_retry<J, Q>(
+        &mut self,
+        method: Method,
+        end_point: EndPoint,
+        query: &Q,
+        json: &J,
+        call: &ApiCall,
+    ) -> ClientResult<Response>
     where
         J: Serialize,
         Q: Serialize,
@@ -182,9 +399,11 @@ impl Client {
         let _ = self.get_token().await?;
         let response_result = self.q(method, end_point, query, json, true).await;
 
-        if let Err(ClientError::ExpiredToken) = response_result {
+        if let Err(ClientError::ExpiredToken { .. }) = response_result {
             debug!("Token expired; refreshing");
             self.refresh_token().await?;
+            call.retry();
+            self.metrics.record_retry(end_point.name());
 
             // try the request again now
             Ok(self.q(method, end_point, query, json, true).await?)
@@ -208,29 +427,18 @@ impl Client {
         J: Serialize,
         Q: Serialize,
     {
-        if max_level() >= LevelFilter::Debug {
-            let text = self
-                .q(method, end_point, query, json, use_token)
-                .await?
-                .body_string()
-                .await?;
-            match serde_json::from_str(&text) {
-                Ok(j) => {
-                    debug!("Deserialized json response body: {}", &text);
-                    Ok(j)
-                }
-                Err(e) => {
-                    debug!("Deserialize json failed for: {}", &text);
-                    Err(ClientError::SerdeJsonError(e))
-                }
-            }
-        } else {
-            Ok(self
-                .q(method, end_point, query, json, use_token)
-                .await?
-                .body_json()
-                .await?)
-        }
+        let call = ApiCall::start(method, end_point);
+        let result = call
+            .instrument(self.q(method, end_point, query, json, use_token))
+            .await;
+        call.finish(&result);
+
+        let context = request_context(method, end_point);
+        let text = read_text(result?, context).await?;
+        decode_json(&text).map_err(|error| ClientError::Decode {
+            context,
+            error: error.into(),
+        })
     }
 
     /// Build and send a single request. Does most of the heavy lifting.
@@ -249,9 +457,12 @@ impl Client {
         let url = self.url_base.build(end_point);
         trace!("Sending request to {}", url);
 
+        let context = request_context(method, end_point);
+        let invalid = |error| ClientError::InvalidRequest { context, error };
         let mut request = Request::builder(method, Url::parse(&url)?)
-            .body(Body::from_json(json)?)
-            .query(query)?;
+            .body(Body::from_json(json).map_err(invalid)?)
+            .query(query)
+            .map_err(invalid)?;
 
         if use_token {
             if let Some(ref t) = self.token_info {
@@ -262,40 +473,102 @@ impl Client {
             }
         }
 
-        let mut response = request.await?;
+        let mut request = request.build();
+        self.middleware.apply(&mut request);
+
+        let start = Instant::now();
+        let result = self.http.send(request).await;
+        let status = result.as_ref().ok().map(|response| response.status());
+        self.metrics.record_request(&RequestMetrics {
+            endpoint: end_point.name(),
+            method,
+            status,
+            status_class: StatusClass::from_status(status),
+            duration: start.elapsed(),
+            bytes_received: result
+                .as_ref()
+                .ok()
+                .and_then(|response| response.len())
+                .map(|len| len as u64),
+        });
+        let mut response = result.map_err(|error| ClientError::Transport { context, error })?;
 
         trace!("response status: {:?}", response.status());
-        match response.status() {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        // Keep the raw body, so the status is never hidden by a body that fails to parse.
+        let body = match response.body_string().await {
+            Ok(body) => body,
+            Err(e) => {
+                debug!("Reading error response body failed: {}", e);
+                String::new()
+            }
+        };
+        match status {
             StatusCode::Unauthorized => {
-                let info: ResponseError = response.body_json().await?;
-                if info.error_description.as_str().contains("expired") {
-                    Err(ClientError::ExpiredToken)
+                let info = serde_json::from_str::<ResponseError>(&body).ok();
+                let expired = info
+                    .as_ref()
+                    .is_some_and(|info| info.error_description.contains("expired"));
+                if expired {
+                    Err(ClientError::ExpiredToken {
+                        context,
+                        status,
+                        body,
+                    })
                 } else {
-                    Err(ClientError::Unauthorized(info))
+                    Err(ClientError::Unauthorized {
+                        context,
+                        status,
+                        body,
+                        info,
+                    })
                 }
             }
-            StatusCode::Forbidden => {
-                let info: ResponseCodeMessageError = response.body_json().await?;
-                Err(ClientError::Forbidden(info))
-            }
-            StatusCode::NotFound => {
-                let info: ResponseCodeMessageError = match response.body_json().await {
-                    Ok(info) => info,
-                    Err(_) => ResponseCodeMessageError {
-                        error: CodeMessage {
-                            code: 404,
-                            message: "Not supplied".to_owned(),
-                        },
-                    },
-                };
-                Err(ClientError::NotFound(info))
-            }
+            StatusCode::Forbidden => Err(ClientError::Forbidden {
+                context,
+                status,
+                info: serde_json::from_str(&body).ok(),
+                body,
+            }),
+            StatusCode::NotFound => Err(ClientError::NotFound {
+                context,
+                status,
+                info: serde_json::from_str(&body).ok(),
+                body,
+            }),
             StatusCode::NotModified => {
                 // reload entry returns this if no changes on re-crawl url or if failed to reload
-                Err(ClientError::NotModified)
+                Err(ClientError::NotModified {
+                    context,
+                    status,
+                    body,
+                })
             }
-            status if status.is_success() => Ok(response),
-            status => Err(ClientError::Other(status, response.body_string().await?)),
+            StatusCode::BadRequest
+            | StatusCode::Conflict
+            | StatusCode::UnprocessableEntity
+            | StatusCode::InternalServerError => match ApiError::from_body(&body) {
+                Some(error) => Err(ClientError::Api {
+                    context,
+                    status,
+                    body,
+                    error,
+                }),
+                None => Err(ClientError::Other {
+                    context,
+                    status,
+                    body,
+                }),
+            },
+            _ => Err(ClientError::Other {
+                context,
+                status,
+                body,
+            }),
         }
     }
 
@@ -304,7 +577,8 @@ impl Client {
     /// Returns a hashmap where the urls given are the keys and the values are either:
     ///
     /// - `None`: no existing entry corresponding to the url
-    /// - `Some(ID)`: an entry exists and here's the ID
+    /// - `Some(EntryId)`: an entry exists and here's the id
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn check_urls_exist<T: Into<String>>(
         &mut self,
         urls: Vec<T>,
@@ -321,9 +595,13 @@ impl Client {
             .await
     }
 
-    /// Check if a url already has a corresponding entry. Returns `None` if not existing or the ID
+    /// Check if a url already has a corresponding entry. Returns `None` if not existing or the id
     /// of the entry if it does exist.
-    pub async fn check_url_exists<T: Into<String>>(&mut self, url: T) -> ClientResult<Option<ID>> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn check_url_exists<T: Into<String>>(
+        &mut self,
+        url: T,
+    ) -> ClientResult<Option<EntryId>> {
         let mut params = HashMap::new();
         params.insert("url".to_owned(), url.into());
         params.insert("return_id".to_owned(), "1".to_owned());
@@ -337,40 +615,63 @@ impl Client {
     }
 
     /// Create a new entry. See docs for `NewEntry` for more information.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn create_entry(&mut self, new_entry: &NewEntry) -> ClientResult<Entry> {
-        self.smart_json_q(Method::Post, EndPoint::Entries, UNIT, new_entry)
-            .await
+        new_entry.validate()?;
+        if new_entry.tags.is_some() {
+            self.update_cache(|cache| cache.invalidate(EndPoint::Tags));
+        }
+        let entry: Entry = self
+            .smart_json_q(Method::Post, EndPoint::Entries, UNIT, new_entry)
+            .await?;
+        // The server updates the existing entry if the url is saved already.
+        self.update_cache(|cache| cache.invalidate_entry(entry.id));
+        Ok(entry)
     }
 
     /// Update entry. To leave an editable field unchanged, set to `None`.
-    pub async fn update_entry<T: Into<ID>>(
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn update_entry<T: Into<EntryId>>(
         &mut self,
         id: T,
         entry: &PatchEntry,
     ) -> ClientResult<Entry> {
-        self.smart_json_q(Method::Patch, EndPoint::Entry(id.into()), UNIT, entry)
+        entry.validate()?;
+        let id = id.into();
+        self.update_cache(|cache| {
+            cache.invalidate_entry(id);
+            if entry.tags.is_some() {
+                cache.invalidate(EndPoint::Tags);
+            }
+        });
+        self.smart_json_q(Method::Patch, EndPoint::Entry(id), UNIT, entry)
             .await
     }
 
     /// Reload entry. This tells the server to re-fetch content from the url (or
     /// origin url?) and use the result to refresh the entry contents.
     ///
-    /// This returns `Err(ClientError::NotModified)` if the server either could
+    /// This returns `Err(ClientError::NotModified { .. })` if the server either could
     /// not refresh the contents, or the content does not get modified.
-    pub async fn reload_entry<T: Into<ID>>(&mut self, id: T) -> ClientResult<Entry> {
-        self.smart_json_q(Method::Patch, EndPoint::EntryReload(id.into()), UNIT, UNIT)
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn reload_entry<T: Into<EntryId>>(&mut self, id: T) -> ClientResult<Entry> {
+        let id = id.into();
+        self.update_cache(|cache| cache.invalidate_entry(id));
+        self.smart_json_q(Method::Patch, EndPoint::EntryReload(id), UNIT, UNIT)
             .await
     }
 
     /// Get an entry by id.
-    pub async fn get_entry<T: Into<ID>>(&mut self, id: T) -> ClientResult<Entry> {
-        self.smart_json_q(Method::Get, EndPoint::Entry(id.into()), UNIT, UNIT)
-            .await
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn get_entry<T: Into<EntryId>>(&mut self, id: T) -> ClientResult<Entry> {
+        self.cached_json_q(EndPoint::Entry(id.into())).await
     }
 
     /// Delete an entry by id.
-    pub async fn delete_entry<T: Into<ID>>(&mut self, id: T) -> ClientResult<Entry> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn delete_entry<T: Into<EntryId>>(&mut self, id: T) -> ClientResult<Entry> {
         let id = id.into();
+        self.update_cache(|cache| cache.invalidate_entry(id));
         let json: DeletedEntry = self
             .smart_json_q(Method::Delete, EndPoint::Entry(id), UNIT, UNIT)
             .await?;
@@ -410,7 +711,10 @@ impl Client {
     }
 
     /// Update an annotation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn update_annotation(&mut self, annotation: &Annotation) -> ClientResult<Annotation> {
+        annotation.validate()?;
+        self.update_cache(|cache| cache.invalidate_all_annotations());
         self.smart_json_q(
             Method::Put,
             EndPoint::Annotation(annotation.id),
@@ -421,14 +725,18 @@ impl Client {
     }
 
     /// Create a new annotation on an entry.
-    pub async fn create_annotation<T: Into<ID>>(
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn create_annotation<T: Into<EntryId>>(
         &mut self,
         entry_id: T,
         annotation: &NewAnnotation,
     ) -> ClientResult<Annotation> {
+        annotation.validate()?;
+        let entry_id = entry_id.into();
+        self.update_cache(|cache| cache.invalidate_entry(entry_id));
         self.smart_json_q(
             Method::Post,
-            EndPoint::Annotation(entry_id.into()),
+            EndPoint::Annotations(entry_id),
             UNIT,
             annotation,
         )
@@ -436,25 +744,118 @@ impl Client {
     }
 
     /// Delete an annotation by id
-    pub async fn delete_annotation<T: Into<ID>>(&mut self, id: T) -> ClientResult<Annotation> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn delete_annotation<T: Into<AnnotationId>>(
+        &mut self,
+        id: T,
+    ) -> ClientResult<Annotation> {
+        self.update_cache(|cache| cache.invalidate_all_annotations());
         self.smart_json_q(Method::Delete, EndPoint::Annotation(id.into()), UNIT, UNIT)
             .await
     }
 
     /// Get all annotations for an entry (by id).
-    pub async fn get_annotations<T: Into<ID>>(&mut self, id: T) -> ClientResult<Annotations> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn get_annotations<T: Into<EntryId>>(&mut self, id: T) -> ClientResult<Annotations> {
+        let json: AnnotationRows = self.cached_json_q(EndPoint::Annotations(id.into())).await?;
+        Ok(json.rows)
+    }
+
+    /// Get the annotations of many entries.
+    ///
+    /// Entries from a listing often already include their annotations, which are used as is.
+    /// The annotations of the other entries are fetched with up to `max_concurrency` requests at
+    /// a time, after the first one, which refreshes the token if it expired. These requests
+    /// bypass the response cache. If any of them fails, the first error is returned.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn get_annotations_for_entries<'a, I>(
+        &mut self,
+        entries: I,
+        max_concurrency: usize,
+    ) -> ClientResult<HashMap<EntryId, Annotations>>
+    where
+        I: IntoIterator<Item = &'a Entry>,
+    {
+        let mut annotations = HashMap::new();
+        let mut missing = Vec::new();
+        for entry in entries {
+            match entry.annotations {
+                Some(ref embedded) => {
+                    annotations.insert(entry.id, embedded.clone());
+                }
+                None => missing.push(entry.id),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(annotations);
+        }
+
+        // Fetch the first one here, so an expired token is refreshed before the workers copy it.
+        // Refresh tokens can only be used once, so workers refreshing it at once would fail.
+        let first = missing.remove(0);
         let json: AnnotationRows = self
-            .smart_json_q(Method::Get, EndPoint::Annotation(id.into()), UNIT, UNIT)
+            .smart_json_q(Method::Get, EndPoint::Annotations(first), UNIT, UNIT)
             .await?;
-        Ok(json.rows)
+        annotations.insert(first, json.rows);
+        if missing.is_empty() {
+            return Ok(annotations);
+        }
+
+        let workers = max_concurrency.clamp(1, missing.len());
+        let queue = Mutex::new(missing.into_iter());
+        let results = try_join_all((0..workers).map(|_| {
+            let mut client = self.fork();
+            let queue = &queue;
+            async move {
+                let mut fetched = Vec::new();
+                loop {
+                    let next = queue.lock().unwrap().next();
+                    let id = match next {
+                        Some(id) => id,
+                        None => break,
+                    };
+                    fetched.push((id, client.get_annotations(id).await?));
+                }
+                Ok::<_, ClientError>((client.token_info, fetched))
+            }
+        }))
+        .await?;
+
+        for (token_info, fetched) in results {
+            // A worker refreshed the token, keep the new one.
+            if token_info != self.token_info {
+                self.token_info = token_info;
+            }
+            annotations.extend(fetched);
+        }
+        Ok(annotations)
+    }
+
+    /// A client with the same configuration and token, for sending requests concurrently. The
+    /// cache isn't shared, so it is disabled.
+    fn fork(&self) -> Client {
+        Client {
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+            token_info: self.token_info.clone(),
+            url_base: self.url_base.clone(),
+            cache: None,
+            http: self.http.clone(),
+            middleware: self.middleware.clone(),
+            metrics: self.metrics.clone(),
+        }
     }
 
     /// Get all entries.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn get_entries(&mut self) -> ClientResult<Entries> {
         self._get_entries(&EntriesFilter::default()).await
     }
 
     /// Get all entries, filtered by filter parameters.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn get_entries_with_filter(
         &mut self,
         filter: &EntriesFilter,
@@ -465,6 +866,7 @@ impl Client {
     /// Get a page of entries, specified by page number. Useful when the expected list of results
     /// is very large and you don't want to wait too long before getting a subset of the entries.
     /// Will return a not found error if `page_number` is out of bounds.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn get_entries_page(
         &mut self,
         filter: &EntriesFilter,
@@ -501,7 +903,7 @@ impl Client {
                 .smart_json_q(Method::Get, EndPoint::Entries, &params, UNIT)
                 .await?;
 
-            entries.extend(json.embedded.items.into_iter());
+            entries.extend(json.embedded.items);
 
             if json.page < json.pages {
                 params.page = json.page + 1;
@@ -513,8 +915,10 @@ impl Client {
         Ok(entries)
     }
 
-    /// Get an export of an entry in a particular format.
-    pub async fn export_entry<T: Into<ID>>(
+    /// Get an export of an entry in a particular format. The export is returned as text, so use
+    /// `export_entry_bytes` for binary formats.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn export_entry<T: Into<EntryId>>(
         &mut self,
         entry_id: T,
         fmt: Format,
@@ -528,50 +932,253 @@ impl Client {
         .await
     }
 
-    /// Get a list of all tags for an entry by entry id.
-    pub async fn get_tags_for_entry<T: Into<ID>>(&mut self, entry_id: T) -> ClientResult<Tags> {
-        self.smart_json_q(
+    /// Get an export of an entry in a particular format as raw bytes. Works for all formats,
+    /// including the binary ones.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn export_entry_bytes<T: Into<EntryId>>(
+        &mut self,
+        entry_id: T,
+        fmt: Format,
+    ) -> ClientResult<Vec<u8>> {
+        self.smart_bytes_q(
             Method::Get,
-            EndPoint::EntryTags(entry_id.into()),
+            EndPoint::Export(entry_id.into(), fmt),
             UNIT,
             UNIT,
         )
         .await
     }
 
+    /// Write an export of an entry to `writer` while it is being received, without loading it
+    /// into memory. Fails with `ExportError::ContentType` if the response isn't an export in the
+    /// requested format. Returns the number of bytes written.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn export_entry_to<T, W>(
+        &mut self,
+        entry_id: T,
+        fmt: Format,
+        writer: &mut W,
+    ) -> ClientResult<u64>
+    where
+        T: Into<EntryId>,
+        W: AsyncWrite + Unpin + Send,
+    {
+        self.export_entry_to_with_progress(entry_id, fmt, writer, |_| {})
+            .await
+    }
+
+    /// Like `export_entry_to`, but calls `progress` after each chunk is written.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn export_entry_to_with_progress<T, W, F>(
+        &mut self,
+        entry_id: T,
+        fmt: Format,
+        writer: &mut W,
+        mut progress: F,
+    ) -> ClientResult<u64>
+    where
+        T: Into<EntryId>,
+        W: AsyncWrite + Unpin + Send,
+        F: FnMut(ExportProgress) + Send,
+    {
+        let response = self
+            .smart_q(
+                Method::Get,
+                EndPoint::Export(entry_id.into(), fmt),
+                UNIT,
+                UNIT,
+            )
+            .await?;
+        write_export(response, fmt, writer, &mut progress).await
+    }
+
+    /// Write an export of all entries matching the filter to `writer`.
+    ///
+    /// If the filter matches one of the categories the web interface can export (all, unread,
+    /// archived or starred entries, or the entries with a single tag), the export of the web
+    /// interface is tried first. Servers usually only allow it for logged in users, in which case
+    /// the entries are fetched and combined locally instead. This is only possible for the JSON
+    /// and CSV formats, others fail with `ExportError::UnsupportedFormat`. The local export has
+    /// the same fields as the export of the web interface.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn export_entries_to<W>(
+        &mut self,
+        filter: &EntriesFilter,
+        fmt: Format,
+        writer: &mut W,
+    ) -> ClientResult<BulkExport>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        if let Some((category, tag)) = export_category(filter) {
+            match self.export_category_to(category, tag, fmt, writer).await {
+                Ok(Some(bytes)) => {
+                    return Ok(BulkExport {
+                        source: ExportSource::Server,
+                        bytes,
+                    })
+                }
+                Ok(None) => {}
+                Err(err @ ClientError::Export(ExportError::ContentType { .. })) => {
+                    debug!("Category export not available: {}", err);
+                }
+                Err(err) if err.status().is_some() => {
+                    debug!("Category export not available: {}", err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        if !matches!(fmt, Format::JSON | Format::CSV) {
+            return Err(ExportError::UnsupportedFormat(fmt).into());
+        }
+        let mut bytes = 0;
+        let mut entries = 0;
+        let mut write = |chunk: String| {
+            bytes += chunk.len() as u64;
+            chunk
+        };
+        let header = match fmt {
+            Format::JSON => "[".to_owned(),
+            _ => CSV_HEADER.to_owned(),
+        };
+        writer.write_all(write(header).as_bytes()).await?;
+        let mut page_number = 1;
+        loop {
+            let page = match self.get_entries_page(filter, page_number).await {
+                // The first page is missing if no entries match.
+                Err(err) if page_number == 1 && err.is_not_found() => break,
+                page => page?,
+            };
+            for entry in &page.entries {
+                let chunk = match fmt {
+                    Format::JSON if entries == 0 => json_record(entry).to_string(),
+                    Format::JSON => format!(",{}", json_record(entry)),
+                    _ => csv_row(entry),
+                };
+                writer.write_all(write(chunk).as_bytes()).await?;
+                entries += 1;
+            }
+            if page_number >= page.total_pages {
+                break;
+            }
+            page_number += 1;
+        }
+        if fmt == Format::JSON {
+            writer.write_all(write("]".to_owned()).as_bytes()).await?;
+        }
+        writer.flush().await?;
+        Ok(BulkExport {
+            source: ExportSource::Local { entries },
+            bytes,
+        })
+    }
+
+    /// Write the export of a category by the web interface to `writer`. Returns `None` without
+    /// sending the request if the tag doesn't exist.
+    async fn export_category_to<W>(
+        &mut self,
+        category: &'static str,
+        tag: Option<&str>,
+        fmt: Format,
+        writer: &mut W,
+    ) -> ClientResult<Option<u64>>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        // The web interface selects the tag by its slug.
+        let slug = match tag {
+            Some(label) => match self
+                .get_tags()
+                .await?
+                .into_iter()
+                .find(|t| t.label == label)
+            {
+                Some(tag) => Some(tag.slug),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+        let mut query = HashMap::new();
+        if let Some(slug) = slug {
+            query.insert("tag", slug);
+        }
+        let response = self
+            .smart_q(
+                Method::Get,
+                EndPoint::CategoryExport(category, fmt),
+                &query,
+                UNIT,
+            )
+            .await?;
+        write_export(response, fmt, writer, &mut |_| {})
+            .await
+            .map(Some)
+    }
+
+    /// Get an export of an entry in JSON, CSV or XML format, and decode it. Other formats are
+    /// rejected without sending a request. See the `export` module for more information.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn export_entry_records<T: Into<EntryId>>(
+        &mut self,
+        entry_id: T,
+        fmt: Format,
+    ) -> ClientResult<Vec<ExportedEntry>> {
+        if fmt.is_binary() || fmt == Format::TXT {
+            return Err(ExportError::UnsupportedFormat(fmt).into());
+        }
+        let body = self.export_entry(entry_id, fmt).await?;
+        Ok(decode(fmt, &body)?)
+    }
+
+    /// Get a list of all tags for an entry by entry id.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn get_tags_for_entry<T: Into<EntryId>>(
+        &mut self,
+        entry_id: T,
+    ) -> ClientResult<Tags> {
+        self.cached_json_q(EndPoint::EntryTags(entry_id.into()))
+            .await
+    }
+
     /// Add tags to an entry by entry id. Idempotent operation. No problems if
     /// tags list is empty.
-    pub async fn add_tags_to_entry<T: Into<ID>, U: Into<String>>(
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn add_tags_to_entry<T: Into<EntryId>, U: Into<String>>(
         &mut self,
         entry_id: T,
         tags: Vec<U>,
     ) -> ClientResult<Entry> {
+        let tags = tags.into_iter().map(|x| x.into()).collect::<Vec<String>>();
+        validate_tags(&tags)?;
+
+        let entry_id = entry_id.into();
+        self.update_cache(|cache| {
+            cache.invalidate_entry(entry_id);
+            cache.invalidate(EndPoint::Tags);
+        });
+
         let mut data = HashMap::new();
-        data.insert(
-            "tags",
-            tags.into_iter().map(|x| x.into()).collect::<Vec<String>>(),
-        );
+        data.insert("tags", tags);
 
-        self.smart_json_q(
-            Method::Post,
-            EndPoint::EntryTags(entry_id.into()),
-            UNIT,
-            &data,
-        )
-        .await
+        self.smart_json_q(Method::Post, EndPoint::EntryTags(entry_id), UNIT, &data)
+            .await
     }
 
     /// Delete a tag (by id) from an entry (by id). Returns err 404 if entry or
     /// tag not found. Idempotent. Removing a tag that exists but doesn't exist
     /// on the entry completes without error.
-    pub async fn delete_tag_from_entry<T: Into<ID>, U: Into<ID>>(
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn delete_tag_from_entry<T: Into<EntryId>, U: Into<TagId>>(
         &mut self,
         entry_id: T,
         tag_id: U,
     ) -> ClientResult<Entry> {
+        let entry_id = entry_id.into();
+        self.update_cache(|cache| cache.invalidate_entry(entry_id));
         self.smart_json_q(
             Method::Delete,
-            EndPoint::DeleteEntryTag(entry_id.into(), tag_id.into()),
+            EndPoint::DeleteEntryTag(entry_id, tag_id.into()),
             UNIT,
             UNIT,
         )
@@ -579,16 +1186,18 @@ impl Client {
     }
 
     /// Get a list of all tags.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn get_tags(&mut self) -> ClientResult<Tags> {
-        self.smart_json_q(Method::Get, EndPoint::Tags, UNIT, UNIT)
-            .await
+        self.cached_json_q(EndPoint::Tags).await
     }
 
     /// Permanently delete a tag by id. This removes the tag from all entries.
     /// Appears to return success if attempting to delete a tag by id that
     /// exists on the server but isn't accessible to the user.
-    pub async fn delete_tag<T: Into<ID>>(&mut self, id: T) -> ClientResult<Tag> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn delete_tag<T: Into<TagId>>(&mut self, id: T) -> ClientResult<Tag> {
         let id = id.into();
+        self.invalidate_tags_everywhere();
 
         // api does not return id of deleted tag, hence the temporary struct
         let dt: DeletedTag = self
@@ -609,10 +1218,13 @@ impl Client {
     /// query.
     ///
     /// Note: this allows deleting a tag with a comma by label.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn delete_tag_by_label<T: Into<String>>(
         &mut self,
         label: T,
     ) -> ClientResult<DeletedTag> {
+        self.invalidate_tags_everywhere();
+
         let mut params = HashMap::new();
         params.insert("tag".to_owned(), label.into());
 
@@ -633,10 +1245,13 @@ impl Client {
     ///
     /// Returns a list of tags that were deleted (sans IDs). Returns 404 not
     /// found _only_ if _all_ tags were not found.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn delete_tags_by_label(
         &mut self,
         tags: Vec<TagString>,
     ) -> ClientResult<Vec<DeletedTag>> {
+        self.invalidate_tags_everywhere();
+
         let mut params = HashMap::new();
         params.insert(
             "tags",
@@ -652,20 +1267,30 @@ impl Client {
             .await
     }
 
+    /// Invalidate the cached tag list and every cached entry, since deleting a tag removes it from
+    /// all entries.
+    fn invalidate_tags_everywhere(&mut self) {
+        self.update_cache(|cache| {
+            cache.invalidate(EndPoint::Tags);
+            cache.invalidate_all_entries();
+        });
+    }
+
     /// Get the API version. Probably not useful because if the version isn't v2
     /// then this library won't work anyway.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn get_api_version(&mut self) -> ClientResult<String> {
-        self.smart_json_q(Method::Get, EndPoint::Version, UNIT, UNIT)
-            .await
+        self.cached_json_q(EndPoint::Version).await
     }
 
     /// Get the currently logged in user information.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn get_user(&mut self) -> ClientResult<User> {
-        self.smart_json_q(Method::Get, EndPoint::User, UNIT, UNIT)
-            .await
+        self.cached_json_q(EndPoint::User).await
     }
 
     /// Register a user and create a client.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub async fn register_user(
         &mut self,
         info: &RegisterInfo,
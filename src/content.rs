@@ -0,0 +1,552 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversion of entry content from HTML to plain text and Markdown. Requires the `content`
+//! feature.
+//!
+//! Plain text is meant for search indexing: it keeps paragraph breaks, but drops all markup, and
+//! replaces images by their alt text. Markdown follows CommonMark, resolves relative links and
+//! images against the url of the entry, and keeps images as image references.
+//!
+//! `ContentText` maps the ranges of annotations to the text of the content, and back.
+//! `reanchor_annotations` moves annotations to the new content after an entry is reloaded.
+//!
+//! ```
+//! use wallabag_api::content::{html_to_markdown, html_to_text};
+//!
+//! let html = r#"<h1>Title</h1><p>Some <em>text</em> with a <a href="/next">link</a>.</p>"#;
+//! assert_eq!(html_to_text(html), "Title\n\nSome text with a link.");
+//! assert_eq!(
+//!     html_to_markdown(html, Some("https://example.com/article")),
+//!     "# Title\n\nSome *text* with a [link](https://example.com/next)."
+//! );
+//! ```
+
+use scraper::node::Node;
+use scraper::{ElementRef, Html};
+use surf::Url;
+
+use crate::types::Entry;
+
+mod anchor;
+mod reanchor;
+
+pub use anchor::{Anchor, ContentText, DEFAULT_CONTEXT};
+pub use reanchor::{
+    reanchor_annotations, LostAnnotation, LostReason, ReanchorReport, Reanchored, MAX_ERROR_RATE,
+};
+
+impl Entry {
+    /// The content of the entry as plain text.
+    pub fn content_text(&self) -> Option<String> {
+        self.content.as_deref().map(html_to_text)
+    }
+
+    /// The content of the entry as CommonMark, with links resolved against the url of the entry.
+    pub fn content_markdown(&self) -> Option<String> {
+        self.content
+            .as_deref()
+            .map(|html| html_to_markdown(html, self.url.as_deref()))
+    }
+}
+
+/// Convert HTML to plain text.
+pub fn html_to_text(html: &str) -> String {
+    Converter {
+        markdown: false,
+        base_url: None,
+    }
+    .convert(html)
+}
+
+/// Convert HTML to CommonMark. Relative links and images are resolved against `base_url`, if it
+/// is a valid url.
+pub fn html_to_markdown(html: &str, base_url: Option<&str>) -> String {
+    Converter {
+        markdown: true,
+        base_url: base_url.and_then(|url| Url::parse(url).ok()),
+    }
+    .convert(html)
+}
+
+/// Elements whose content is never shown.
+const HIDDEN: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "iframe", "object", "svg",
+];
+
+/// Elements that are rendered as a separate paragraph.
+const BLOCKS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "aside",
+    "nav",
+    "figure",
+    "figcaption",
+    "address",
+    "details",
+    "summary",
+    "dl",
+    "dt",
+    "dd",
+    "li",
+];
+
+struct Converter {
+    markdown: bool,
+    base_url: Option<Url>,
+}
+
+impl Converter {
+    fn convert(&self, html: &str) -> String {
+        let document = Html::parse_fragment(html);
+        let text = self.children(document.root_element());
+        normalize(&text)
+    }
+
+    /// Render the children of an element.
+    fn children(&self, element: ElementRef) -> String {
+        let mut out = String::new();
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => {
+                    let line_start = out.is_empty() || out.ends_with('\n');
+                    push_inline(&mut out, &self.escape(&collapse(text), line_start));
+                }
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child, &mut out);
+                    }
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+
+    fn element(&self, element: ElementRef, out: &mut String) {
+        let name = element.value().name();
+        match name {
+            _ if HIDDEN.contains(&name) => {}
+            "br" => {
+                trim_end_spaces(out);
+                out.push_str(if self.markdown { "\\\n" } else { "\n" });
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = single_line(&self.children(element));
+                if self.markdown {
+                    let level = usize::from(name.as_bytes()[1] - b'0');
+                    push_block(out, &format!("{} {}", "#".repeat(level), text));
+                } else {
+                    push_block(out, &text);
+                }
+            }
+            "ul" | "ol" => push_block(out, &self.list(element, name == "ol")),
+            "blockquote" => {
+                let text = normalize(&self.children(element));
+                if self.markdown {
+                    push_block(out, &prefix_lines(&text, "> ", ">"));
+                } else {
+                    push_block(out, &text);
+                }
+            }
+            "pre" => {
+                let text: String = element.text().collect();
+                let text = text.trim_end_matches('\n');
+                if self.markdown {
+                    let fence = if text.contains("```") { "~~~" } else { "```" };
+                    push_block(out, &format!("{}\n{}\n{}", fence, text, fence));
+                } else {
+                    push_block(out, text);
+                }
+            }
+            "hr" => push_block(out, if self.markdown { "---" } else { "" }),
+            "table" => push_block(out, &self.table(element)),
+            _ if BLOCKS.contains(&name) => push_block(out, &self.children(element)),
+            "strong" | "b" => self.wrap(element, "**", out),
+            "em" | "i" => self.wrap(element, "*", out),
+            "code" | "kbd" | "samp" => {
+                let text: String = element.text().collect();
+                if self.markdown {
+                    let code = if text.contains('`') {
+                        format!("`` {} ``", text)
+                    } else {
+                        format!("`{}`", text)
+                    };
+                    push_inline(out, &code);
+                } else {
+                    push_inline(out, &collapse(&text));
+                }
+            }
+            "a" => {
+                let text = self.children(element);
+                match element.attr("href").and_then(|href| self.link_target(href)) {
+                    Some(href) if self.markdown => {
+                        if text.trim().is_empty() {
+                            push_inline(out, &format!("<{}>", href));
+                        } else {
+                            push_inline(out, &format!("[{}]({})", text.trim(), href));
+                        }
+                    }
+                    _ => push_inline(out, &text),
+                }
+            }
+            "img" => {
+                let alt = element.attr("alt").unwrap_or_default();
+                match element.attr("src") {
+                    Some(src) if self.markdown => {
+                        let src = self.resolve(src);
+                        let title = match element.attr("title") {
+                            Some(title) => format!(" \"{}\"", title.replace('"', "\\\"")),
+                            None => String::new(),
+                        };
+                        push_inline(
+                            out,
+                            &format!("![{}]({}{})", self.escape(alt, false), src, title),
+                        );
+                    }
+                    _ => push_inline(out, &collapse(alt)),
+                }
+            }
+            _ => {
+                let text = self.children(element);
+                push_inline(out, &text);
+            }
+        }
+    }
+
+    /// Render inline content between markers, keeping surrounding whitespace outside of them.
+    fn wrap(&self, element: ElementRef, marker: &str, out: &mut String) {
+        let text = self.children(element);
+        if !self.markdown || text.trim().is_empty() {
+            push_inline(out, &text);
+            return;
+        }
+        if text.starts_with(' ') {
+            push_inline(out, " ");
+        }
+        push_inline(out, &format!("{}{}{}", marker, text.trim(), marker));
+        if text.ends_with(' ') {
+            out.push(' ');
+        }
+    }
+
+    fn list(&self, element: ElementRef, ordered: bool) -> String {
+        let start: usize = element
+            .attr("start")
+            .and_then(|start| start.parse().ok())
+            .unwrap_or(1);
+        element
+            .child_elements()
+            .filter(|child| child.value().name() == "li")
+            .enumerate()
+            .map(|(i, item)| {
+                let marker = if ordered {
+                    format!("{}. ", start + i)
+                } else {
+                    "- ".to_owned()
+                };
+                // items are rendered as a tight list
+                let text = normalize(&self.children(item)).replace("\n\n", "\n");
+                let indent = " ".repeat(marker.len());
+                let mut lines = text.lines();
+                let mut rendered = format!("{}{}", marker, lines.next().unwrap_or_default());
+                for line in lines {
+                    rendered.push('\n');
+                    if !line.is_empty() {
+                        rendered.push_str(&indent);
+                        rendered.push_str(line);
+                    }
+                }
+                rendered
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn table(&self, element: ElementRef) -> String {
+        let rows: Vec<Vec<String>> = element
+            .descendent_elements()
+            .filter(|row| row.value().name() == "tr")
+            .map(|row| {
+                row.child_elements()
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .map(|cell| single_line(&self.children(cell)))
+                    .collect()
+            })
+            .filter(|cells: &Vec<String>| !cells.is_empty())
+            .collect();
+        if !self.markdown {
+            return rows
+                .iter()
+                .map(|cells| cells.join("\t"))
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let format_row = |cells: &[String]| {
+            let mut cells: Vec<String> =
+                cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            cells.resize(columns, String::new());
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = Vec::new();
+        for (i, cells) in rows.iter().enumerate() {
+            lines.push(format_row(cells));
+            if i == 0 {
+                lines.push(format!("|{}", " --- |".repeat(columns)));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// The url a link points to, or `None` for links that can't be followed from outside the
+    /// entry.
+    fn link_target(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            None
+        } else {
+            Some(self.resolve(href))
+        }
+    }
+
+    fn resolve(&self, url: &str) -> String {
+        let url = url.trim();
+        let resolved = match self.base_url {
+            Some(ref base) => base.join(url).map(|url| url.to_string()).ok(),
+            None => None,
+        };
+        resolved
+            .unwrap_or_else(|| url.to_owned())
+            .replace(' ', "%20")
+            .replace('(', "%28")
+            .replace(')', "%29")
+    }
+
+    /// Escape text for Markdown. At the start of a line, markers that would start a heading or a
+    /// list are escaped as well.
+    fn escape(&self, text: &str, line_start: bool) -> String {
+        if !self.markdown {
+            return text.to_owned();
+        }
+        let mut escaped = String::with_capacity(text.len());
+        let mut text = text;
+        if line_start {
+            let trimmed = text.trim_start();
+            escaped.push_str(&text[..text.len() - trimmed.len()]);
+            let number = trimmed.len()
+                - trimmed
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            let (marker, rest) = trimmed.split_at(number);
+            escaped.push_str(marker);
+            if (number > 0 && (rest.starts_with('.') || rest.starts_with(')')))
+                || (number == 0 && rest.starts_with(['#', '-', '+', '=']))
+            {
+                escaped.push('\\');
+            }
+            text = rest;
+        }
+        for c in text.chars() {
+            if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '!') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+}
+
+/// Collapse whitespace the way HTML renders it.
+fn collapse(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !space {
+                collapsed.push(' ');
+            }
+            space = true;
+        } else {
+            collapsed.push(c);
+            space = false;
+        }
+    }
+    collapsed
+}
+
+fn single_line(text: &str) -> String {
+    collapse(&text.replace("\\\n", " ")).trim().to_owned()
+}
+
+/// Append inline text, dropping whitespace at the start of a line and double spaces.
+fn push_inline(out: &mut String, text: &str) {
+    let at_line_start = out.is_empty() || out.ends_with('\n');
+    let text = if at_line_start || out.ends_with(' ') {
+        text.trim_start_matches(' ')
+    } else {
+        text
+    };
+    out.push_str(text);
+}
+
+/// Append a block, separated from the surrounding content by an empty line.
+fn push_block(out: &mut String, block: &str) {
+    let block = block.trim_matches('\n');
+    trim_end_spaces(out);
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    if !block.trim().is_empty() {
+        out.push_str(block);
+        out.push_str("\n\n");
+    }
+}
+
+fn trim_end_spaces(out: &mut String) {
+    while out.ends_with(' ') {
+        out.pop();
+    }
+}
+
+fn prefix_lines(text: &str, prefix: &str, empty_prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                empty_prefix.to_owned()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Remove trailing whitespace from lines and collapse runs of empty lines.
+fn normalize(text: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"
+        <h2>A  heading</h2>
+        <p>First <strong>bold</strong> and <em>emphasised</em> text,
+           with <code>code</code> and a <a href="../other.html">relative link</a>.</p>
+        <figure><img src="/images/cat.jpg" alt="A cat"><figcaption>The cat</figcaption></figure>
+        <ul><li>One</li><li>Two<ol><li>Nested</li></ol></li></ul>
+        <blockquote><p>Quoted</p><p>Twice</p></blockquote>
+        <pre>fn main() {
+    println!("hi");
+}</pre>
+        <script>alert("hidden")</script>
+        <table><tr><th>Name</th><th>Value</th></tr><tr><td>a|b</td><td>1</td></tr></table>
+    "#;
+
+    #[test]
+    fn test_html_to_markdown() {
+        assert_eq!(
+            html_to_markdown(ARTICLE, Some("https://example.com/posts/article.html")),
+            r#"## A heading
+
+First **bold** and *emphasised* text, with `code` and a [relative link](https://example.com/other.html).
+
+![A cat](https://example.com/images/cat.jpg)
+
+The cat
+
+- One
+- Two
+  1. Nested
+
+> Quoted
+>
+> Twice
+
+```
+fn main() {
+    println!("hi");
+}
+```
+
+| Name | Value |
+| --- | --- |
+| a\|b | 1 |"#
+        );
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        assert_eq!(
+            html_to_text(ARTICLE),
+            r#"A heading
+
+First bold and emphasised text, with code and a relative link.
+
+A cat
+
+The cat
+
+- One
+- Two
+  1. Nested
+
+Quoted
+
+Twice
+
+fn main() {
+    println!("hi");
+}
+
+Name	Value
+a|b	1"#
+        );
+    }
+
+    #[test]
+    fn test_markdown_escaping_and_breaks() {
+        assert_eq!(
+            html_to_markdown("<p>2 * 3 = [six]<br>next_line</p>", None),
+            "2 \\* 3 = \\[six\\]\\\nnext\\_line"
+        );
+        assert_eq!(html_to_text("<p>a<br>b</p>"), "a\nb");
+    }
+
+    #[test]
+    fn test_markdown_escapes_line_start() {
+        assert_eq!(
+            html_to_markdown(
+                "<p># one</p><p>- two<br>+ three<br>1. four</p><p>2) five - six!</p>",
+                None
+            ),
+            "\\# one\n\n\\- two\\\n\\+ three\\\n1\\. four\n\n2\\) five - six\\!"
+        );
+        assert_eq!(
+            html_to_markdown(
+                "<ul><li>- item</li></ul><blockquote>> quote</blockquote>",
+                None
+            ),
+            "- \\- item\n\n> \\> quote"
+        );
+        assert_eq!(html_to_text("<p># one</p>"), "# one");
+    }
+}
@@ -0,0 +1,428 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Mapping annotation ranges to the text of an entry.
+//!
+//! Annotations store their position in the Annotator format: an XPath to an element, relative to
+//! the content of the entry, and an offset into the text of that element. Offsets count UTF-16
+//! code units, like JavaScript strings do. Positions in the text of the whole content, as used by
+//! `Anchor` and `ContentText::range`, count characters instead.
+//!
+//! ```
+//! use wallabag_api::content::ContentText;
+//!
+//! let content = ContentText::parse("<p>First paragraph.</p><p>Second paragraph.</p>");
+//! let start = content.text().find("Second").unwrap();
+//! let range = content.range(start, start + 6).unwrap();
+//! assert_eq!(range.start.as_deref(), Some("/p[2]"));
+//!
+//! let anchor = content.resolve_range(&range).unwrap();
+//! assert_eq!(anchor.quote, "Second");
+//! assert_eq!(anchor.prefix, "First paragraph.");
+//! ```
+
+use std::collections::HashMap;
+
+use scraper::node::Node;
+use scraper::{ElementRef, Html};
+
+use crate::errors::AnchorError;
+use crate::types::{Annotation, Entry, NewAnnotation, Range};
+
+/// Number of characters of context included before and after an anchor by default.
+pub const DEFAULT_CONTEXT: usize = 32;
+
+/// A span of the text content of an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Anchor {
+    /// Character offset of the start of the span in the text content.
+    pub start: usize,
+    /// Character offset just after the end of the span in the text content.
+    pub end: usize,
+    /// The text of the span, exactly as it is in the content.
+    pub quote: String,
+    /// The text just before the span.
+    pub prefix: String,
+    /// The text just after the span.
+    pub suffix: String,
+}
+
+/// Span of an element in the text content.
+#[derive(Debug, Clone)]
+struct ElementSpan {
+    path: String,
+    start: usize,
+    end: usize,
+}
+
+/// End of a text node in the text content, and the element containing it. A text node starts
+/// where the previous one ends.
+#[derive(Debug, Clone)]
+struct TextSpan {
+    end: usize,
+    element: usize,
+}
+
+/// The text content of an entry, indexed by the XPaths used in annotation ranges.
+///
+/// The text content is the concatenation of all text in the HTML, like the `textContent` of the
+/// element the content is shown in by the web interface.
+#[derive(Debug, Clone)]
+pub struct ContentText {
+    chars: Vec<char>,
+    elements: Vec<ElementSpan>,
+    paths: HashMap<String, usize>,
+    texts: Vec<TextSpan>,
+    context: usize,
+}
+
+impl Entry {
+    /// Find the text highlighted by an annotation in the content of this entry.
+    pub fn resolve_annotation(&self, annotation: &Annotation) -> Result<Anchor, AnchorError> {
+        let content = self.content.as_deref().ok_or(AnchorError::NoContent)?;
+        ContentText::parse(content).resolve(annotation)
+    }
+}
+
+impl ContentText {
+    pub fn parse(html: &str) -> Self {
+        let mut content = ContentText {
+            chars: Vec::new(),
+            elements: Vec::new(),
+            paths: HashMap::new(),
+            texts: Vec::new(),
+            context: DEFAULT_CONTEXT,
+        };
+        let fragment = Html::parse_fragment(html);
+        content.index(fragment.root_element(), String::new());
+        content
+    }
+
+    /// Set the number of characters of context included before and after an anchor.
+    pub fn with_context(mut self, chars: usize) -> Self {
+        self.context = chars;
+        self
+    }
+
+    /// The text content.
+    pub fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    /// The number of characters in the text content.
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Find the text highlighted by an annotation. An annotation with multiple ranges is resolved
+    /// to the span from the start of the first range to the end of the last one.
+    pub fn resolve(&self, annotation: &Annotation) -> Result<Anchor, AnchorError> {
+        let mut span: Option<(usize, usize)> = None;
+        for range in &annotation.ranges {
+            let (start, end) = self.offsets(range)?;
+            span = Some(match span {
+                Some((first, last)) => (first.min(start), last.max(end)),
+                None => (start, end),
+            });
+        }
+        let (start, end) = span.ok_or(AnchorError::NoRanges)?;
+        self.anchor(start, end)
+    }
+
+    /// Find the text covered by a single range.
+    pub fn resolve_range(&self, range: &Range) -> Result<Anchor, AnchorError> {
+        let (start, end) = self.offsets(range)?;
+        self.anchor(start, end)
+    }
+
+    /// The span between two character offsets of the text content, with its context.
+    pub fn anchor(&self, start: usize, end: usize) -> Result<Anchor, AnchorError> {
+        self.check_selection(start, end)?;
+        let text = |from: usize, to: usize| self.chars[from..to].iter().collect::<String>();
+        Ok(Anchor {
+            start,
+            end,
+            quote: text(start, end),
+            prefix: text(start.saturating_sub(self.context), start),
+            suffix: text(end, (end + self.context).min(self.len())),
+        })
+    }
+
+    /// Build the range covering the characters `start..end` of the text content. The start and
+    /// end paths point to the elements directly containing the first and last character.
+    pub fn range(&self, start: usize, end: usize) -> Result<Range, AnchorError> {
+        self.check_selection(start, end)?;
+        if start == end {
+            return Err(AnchorError::InvalidSelection {
+                start,
+                end,
+                len: self.len(),
+            });
+        }
+        let start_element = &self.elements[self.text_at(start).element];
+        let end_element = &self.elements[self.text_at(end - 1).element];
+        Ok(Range {
+            start: Some(display_path(&start_element.path)),
+            end: Some(display_path(&end_element.path)),
+            start_offset: self.utf16_len(start_element.start, start),
+            end_offset: self.utf16_len(end_element.start, end),
+        })
+    }
+
+    /// Build a new annotation highlighting the characters `start..end` of the text content.
+    /// Whitespace at either end of the selection is left out of both the quote and the range.
+    pub fn new_annotation(
+        &self,
+        mut start: usize,
+        mut end: usize,
+        text: String,
+    ) -> Result<NewAnnotation, AnchorError> {
+        self.check_selection(start, end)?;
+        while start < end && self.chars[start].is_whitespace() {
+            start += 1;
+        }
+        while end > start && self.chars[end - 1].is_whitespace() {
+            end -= 1;
+        }
+        let range = self.range(start, end)?;
+        Ok(NewAnnotation {
+            quote: self.chars[start..end].iter().collect(),
+            ranges: vec![range],
+            text,
+        })
+    }
+
+    /// Add the text below `element` to the index. `path` is the canonical path of `element`.
+    fn index(&mut self, element: ElementRef, path: String) {
+        let index = self.elements.len();
+        self.elements.push(ElementSpan {
+            path: path.clone(),
+            start: self.chars.len(),
+            end: self.chars.len(),
+        });
+        self.paths.insert(path.clone(), index);
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => {
+                    self.chars.extend(text.chars());
+                    self.texts.push(TextSpan {
+                        end: self.chars.len(),
+                        element: index,
+                    });
+                }
+                Node::Element(child_element) => {
+                    let name = child_element.name().to_lowercase();
+                    let count = counts.entry(name.clone()).or_insert(0);
+                    *count += 1;
+                    let child_path = format!("{}/{}[{}]", path, name, count);
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.index(child, child_path);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.elements[index].end = self.chars.len();
+    }
+
+    /// Map a range to character offsets in the text content.
+    fn offsets(&self, range: &Range) -> Result<(usize, usize), AnchorError> {
+        let (start, end) = match (&range.start, &range.end) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(AnchorError::MissingPath),
+        };
+        let start = self.position(start, range.start_offset)?;
+        let end = self.position(end, range.end_offset)?;
+        if end < start {
+            return Err(AnchorError::Reversed);
+        }
+        Ok((start, end))
+    }
+
+    /// Map an offset in UTF-16 code units within the element at `path` to a character offset in
+    /// the text content.
+    fn position(&self, path: &str, offset: u32) -> Result<usize, AnchorError> {
+        let element = canonical_path(path)
+            .and_then(|canonical| self.paths.get(&canonical))
+            .map(|&index| &self.elements[index])
+            .ok_or_else(|| AnchorError::PathNotFound {
+                path: path.to_owned(),
+            })?;
+
+        let mut units = 0;
+        let mut position = element.start;
+        while units < offset as usize && position < element.end {
+            units += self.chars[position].len_utf16();
+            position += 1;
+        }
+        // Past the end of the element, or in the middle of a surrogate pair.
+        if units != offset as usize {
+            return Err(AnchorError::OffsetOutOfRange {
+                path: path.to_owned(),
+                offset,
+            });
+        }
+        Ok(position)
+    }
+
+    /// The text node containing the character at `position`.
+    fn text_at(&self, position: usize) -> &TextSpan {
+        let index = self.texts.partition_point(|text| text.end <= position);
+        &self.texts[index]
+    }
+
+    fn utf16_len(&self, start: usize, end: usize) -> u32 {
+        self.chars[start..end]
+            .iter()
+            .map(|c| c.len_utf16() as u32)
+            .sum()
+    }
+
+    fn check_selection(&self, start: usize, end: usize) -> Result<(), AnchorError> {
+        if start > end || end > self.len() {
+            return Err(AnchorError::InvalidSelection {
+                start,
+                end,
+                len: self.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Normalize an XPath as stored in a range: a leading slash is optional, element names are case
+/// insensitive and a missing index means the first element. Returns `None` for steps other than
+/// `name[index]`.
+fn canonical_path(path: &str) -> Option<String> {
+    let mut canonical = String::new();
+    for step in path.split('/').filter(|step| !step.is_empty()) {
+        let (name, index) = match step.find('[') {
+            Some(open) => {
+                let index = step[open + 1..].strip_suffix(']')?.parse::<usize>().ok()?;
+                (&step[..open], index)
+            }
+            None => (step, 1),
+        };
+        if name.is_empty() || index == 0 || !name.chars().all(|c| c.is_alphanumeric() || c == '-') {
+            return None;
+        }
+        canonical.push_str(&format!("/{}[{}]", name.to_lowercase(), index));
+    }
+    Some(canonical)
+}
+
+/// The root of the content is written as "/" rather than an empty path.
+fn display_path(path: &str) -> String {
+    if path.is_empty() {
+        "/".to_owned()
+    } else {
+        path.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str =
+        "<h2>Title</h2>\n<p>Some <em>emphasised</em> text.</p>\n<p>Caf\u{e9} \u{1f600} smile</p>";
+
+    #[test]
+    fn test_resolve_annotator_ranges() {
+        let content = ContentText::parse(CONTENT).with_context(5);
+        let range = Range {
+            start: Some("/p[1]/em[1]".to_owned()),
+            end: Some("/P[1]".to_owned()),
+            start_offset: 0,
+            end_offset: 20,
+        };
+        let anchor = content.resolve_range(&range).unwrap();
+        assert_eq!(anchor.quote, "emphasised text");
+        assert_eq!(anchor.prefix, "Some ");
+        assert_eq!(anchor.suffix, ".\nCaf");
+
+        // The emoji is two UTF-16 code units but one character.
+        let range = Range {
+            start: Some("/p[2]".to_owned()),
+            end: Some("/p[2]".to_owned()),
+            start_offset: 8,
+            end_offset: 13,
+        };
+        assert_eq!(content.resolve_range(&range).unwrap().quote, "smile");
+        let range = Range {
+            start_offset: 6,
+            ..range
+        };
+        assert_eq!(
+            content.resolve_range(&range),
+            Err(AnchorError::OffsetOutOfRange {
+                path: "/p[2]".to_owned(),
+                offset: 6
+            })
+        );
+
+        let range = Range {
+            start: Some("/p[3]".to_owned()),
+            ..range
+        };
+        assert!(matches!(
+            content.resolve_range(&range),
+            Err(AnchorError::PathNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_range_round_trip() {
+        let content = ContentText::parse(CONTENT);
+        let text = content.text();
+        for quote in [
+            "Title",
+            "emphasised",
+            "Some emphasised text",
+            "smile",
+            "text.\nCaf",
+        ] {
+            let byte_start = text.find(quote).unwrap();
+            let start = text[..byte_start].chars().count();
+            let end = start + quote.chars().count();
+            let range = content.range(start, end).unwrap();
+            let anchor = content.resolve_range(&range).unwrap();
+            assert_eq!((anchor.start, anchor.end), (start, end));
+            assert_eq!(anchor.quote, quote);
+        }
+
+        let start = text.find("smile").unwrap();
+        let start = text[..start].chars().count();
+        let range = content.range(start, start + 5).unwrap();
+        assert_eq!(range.start.as_deref(), Some("/p[2]"));
+        assert_eq!((range.start_offset, range.end_offset), (8, 13));
+
+        let annotation = content.new_annotation(0, 6, "note".to_owned()).unwrap();
+        assert_eq!(annotation.quote, "Title");
+        assert_eq!(annotation.ranges, vec![content.range(0, 5).unwrap()]);
+        let anchor = content.resolve_range(&annotation.ranges[0]).unwrap();
+        assert_eq!(anchor.quote, annotation.quote);
+
+        let start = text[..text.find("emphasised").unwrap()].chars().count();
+        let end = start + "emphasised".len();
+        let annotation = content
+            .new_annotation(start - 1, end + 1, String::new())
+            .unwrap();
+        assert_eq!(annotation.quote, "emphasised");
+        assert_eq!(annotation.ranges, vec![content.range(start, end).unwrap()]);
+        assert_eq!(
+            content.range(3, 3),
+            Err(AnchorError::InvalidSelection {
+                start: 3,
+                end: 3,
+                len: content.len()
+            })
+        );
+    }
+}
@@ -0,0 +1,380 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Moving annotations to changed content.
+//!
+//! Reloading an entry replaces its content, which often breaks the ranges of its annotations.
+//! `reanchor_annotations` looks up the quote of each annotation in the new content, and builds
+//! new ranges for it:
+//!
+//! ```no_run
+//! # async fn run(client: &mut wallabag_api::Client) -> wallabag_api::errors::ClientResult<()> {
+//! use wallabag_api::content::reanchor_annotations;
+//!
+//! let old = client.get_entry(1).await?;
+//! let annotations = client.get_annotations(1).await?;
+//! let new = client.reload_entry(1).await?;
+//!
+//! let report = reanchor_annotations(
+//!     old.content.as_deref().unwrap_or_default(),
+//!     new.content.as_deref().unwrap_or_default(),
+//!     &annotations,
+//! );
+//! for moved in &report.updated {
+//!     client.update_annotation(&moved.annotation).await?;
+//! }
+//! for lost in &report.lost {
+//!     println!("annotation {} is lost: {}", lost.id, lost.reason);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use super::anchor::{Anchor, ContentText};
+use crate::errors::AnchorError;
+use crate::types::{Annotation, AnnotationId};
+
+/// Maximum number of edits allowed in a fuzzy match, as a fraction of the length of the quote.
+pub const MAX_ERROR_RATE: f64 = 0.2;
+
+/// The result of `reanchor_annotations`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReanchorReport {
+    /// Annotations whose ranges still point to their quote in the new content.
+    pub unchanged: Vec<AnnotationId>,
+    /// Annotations that were found at another place in the new content.
+    pub updated: Vec<Reanchored>,
+    /// Annotations that couldn't be found in the new content.
+    pub lost: Vec<LostAnnotation>,
+}
+
+/// An annotation with ranges pointing to its quote in the new content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reanchored {
+    /// The annotation with new ranges, ready for `Client::update_annotation`. If the quote
+    /// changed slightly, it is replaced by the text that was matched.
+    pub annotation: Annotation,
+    /// Number of characters inserted, removed or replaced in the quote. 0 for an exact match.
+    pub distance: usize,
+}
+
+/// An annotation that couldn't be re-anchored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LostAnnotation {
+    pub id: AnnotationId,
+    pub reason: LostReason,
+}
+
+/// Represents the reasons an annotation couldn't be re-anchored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LostReason {
+    /// The annotation has no quote, and its ranges don't match the old content either.
+    NoQuote,
+    /// The quote isn't in the new content, not even approximately.
+    NotFound,
+    /// A range for the match couldn't be built.
+    Anchor(AnchorError),
+}
+
+impl fmt::Display for LostReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LostReason::NoQuote => write!(f, "annotation has no quote"),
+            LostReason::NotFound => write!(f, "quote not found in the new content"),
+            LostReason::Anchor(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// Find the annotations of `old_html` in `new_html`.
+///
+/// Annotations whose ranges still cover their quote are left alone. Others are searched for by
+/// their quote, ignoring differences in whitespace. If the quote is found multiple times, the
+/// match with the same surrounding text as in the old content wins. If it isn't found exactly, a
+/// match with at most `MAX_ERROR_RATE` edits is accepted.
+pub fn reanchor_annotations(
+    old_html: &str,
+    new_html: &str,
+    annotations: &[Annotation],
+) -> ReanchorReport {
+    let old = ContentText::parse(old_html);
+    let new = ContentText::parse(new_html);
+    let new_text = Normalized::new(&new.text());
+
+    let mut report = ReanchorReport::default();
+    for annotation in annotations {
+        let old_anchor = old.resolve(annotation).ok();
+        let quote = match (&annotation.quote, &old_anchor) {
+            (Some(quote), _) => quote.clone(),
+            (None, Some(anchor)) => anchor.quote.clone(),
+            (None, None) => {
+                report.lost.push(LostAnnotation {
+                    id: annotation.id,
+                    reason: LostReason::NoQuote,
+                });
+                continue;
+            }
+        };
+        let quote = normalize(&quote);
+
+        if let Ok(anchor) = new.resolve(annotation) {
+            if normalize(&anchor.quote) == quote {
+                report.unchanged.push(annotation.id);
+                continue;
+            }
+        }
+
+        let found = find(&new_text, &quote, old_anchor.as_ref(), old.len())
+            .ok_or(LostReason::NotFound)
+            .and_then(|found| {
+                let start = new_text.positions[found.start];
+                let end = new_text.positions[found.end - 1] + 1;
+                let range = new.range(start, end).map_err(LostReason::Anchor)?;
+                let mut annotation = annotation.clone();
+                if found.distance > 0 {
+                    annotation.quote =
+                        Some(new.anchor(start, end).unwrap().quote.trim().to_owned());
+                }
+                annotation.ranges = vec![range];
+                Ok(Reanchored {
+                    annotation,
+                    distance: found.distance,
+                })
+            });
+        match found {
+            Ok(reanchored) => report.updated.push(reanchored),
+            Err(reason) => report.lost.push(LostAnnotation {
+                id: annotation.id,
+                reason,
+            }),
+        }
+    }
+    report
+}
+
+/// Text with whitespace collapsed, and the position of each character in the original text.
+struct Normalized {
+    chars: Vec<char>,
+    positions: Vec<usize>,
+}
+
+impl Normalized {
+    fn new(text: &str) -> Self {
+        let mut normalized = Normalized {
+            chars: Vec::new(),
+            positions: Vec::new(),
+        };
+        for (position, c) in text.chars().enumerate() {
+            if c.is_whitespace() {
+                if normalized.chars.last().is_none_or(|last| *last == ' ') {
+                    continue;
+                }
+                normalized.chars.push(' ');
+            } else {
+                normalized.chars.push(c);
+            }
+            normalized.positions.push(position);
+        }
+        normalized
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A match of a quote in normalized text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Match {
+    start: usize,
+    end: usize,
+    distance: usize,
+}
+
+/// Find the best match of `quote` in `text`. `old` is where the quote was in the old content, of
+/// `old_len` characters.
+fn find(text: &Normalized, quote: &str, old: Option<&Anchor>, old_len: usize) -> Option<Match> {
+    let quote: Vec<char> = quote.chars().collect();
+    if quote.is_empty() {
+        return None;
+    }
+    let max_distance = (quote.len() as f64 * MAX_ERROR_RATE) as usize;
+    let candidates = matches(&text.chars, &quote, max_distance);
+    let best_distance = candidates.iter().map(|m| m.distance).min()?;
+
+    let prefix: Vec<char> = old
+        .map(|a| normalize(&a.prefix))
+        .unwrap_or_default()
+        .chars()
+        .collect();
+    let suffix: Vec<char> = old
+        .map(|a| normalize(&a.suffix))
+        .unwrap_or_default()
+        .chars()
+        .collect();
+    // Where the quote was, relative to the length of the content.
+    let old_position = old.map_or(0.0, |a| a.start as f64 / old_len.max(1) as f64);
+
+    candidates
+        .into_iter()
+        .filter(|m| m.distance == best_distance)
+        .min_by(|a, b| {
+            let context = |m: &Match| {
+                let before = text.chars[..m.start]
+                    .iter()
+                    .rev()
+                    .skip_while(|c| **c == ' ');
+                let after = text.chars[m.end..].iter().skip_while(|c| **c == ' ');
+                common_len(before, prefix.iter().rev().skip_while(|c| **c == ' '))
+                    + common_len(after, suffix.iter().skip_while(|c| **c == ' '))
+            };
+            let offset = |m: &Match| {
+                let position = m.start as f64 / text.chars.len().max(1) as f64;
+                (position - old_position).abs()
+            };
+            context(b)
+                .cmp(&context(a))
+                .then(offset(a).total_cmp(&offset(b)))
+        })
+}
+
+fn common_len<'a>(a: impl Iterator<Item = &'a char>, b: impl Iterator<Item = &'a char>) -> usize {
+    a.zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// All places where `quote` matches `text` with at most `max_distance` edits. For each end
+/// position only the closest match is returned.
+///
+/// This is Sellers' algorithm: the edit distance table of the quote against the text, where a
+/// match may start anywhere in the text.
+fn matches(text: &[char], quote: &[char], max_distance: usize) -> Vec<Match> {
+    // Distance and start position for each prefix of the quote, ending at the current position.
+    let mut column: Vec<(usize, usize)> = (0..=quote.len()).map(|i| (i, 0)).collect();
+    let mut found = Vec::new();
+    for (j, t) in text.iter().enumerate() {
+        let mut diagonal = column[0];
+        column[0] = (0, j + 1);
+        for (i, q) in quote.iter().enumerate() {
+            let substitute = (diagonal.0 + usize::from(q != t), diagonal.1);
+            let insert = (column[i + 1].0 + 1, column[i + 1].1);
+            let delete = (column[i].0 + 1, column[i].1);
+            diagonal = column[i + 1];
+            column[i + 1] = [substitute, insert, delete]
+                .iter()
+                .copied()
+                .min_by_key(|(distance, _)| *distance)
+                .unwrap();
+        }
+        let (distance, start) = column[quote.len()];
+        if distance <= max_distance && start <= j {
+            found.push(Match {
+                start,
+                end: j + 1,
+                distance,
+            });
+        }
+    }
+    // Keep only the best match of each run of overlapping matches. Of equally close matches, the
+    // one closest in length to the quote is best.
+    let better = |a: &Match, b: &Match| {
+        let length_difference = |m: &Match| (m.end - m.start).abs_diff(quote.len());
+        (a.distance, length_difference(a)) < (b.distance, length_difference(b))
+    };
+    let mut best: Vec<Match> = Vec::new();
+    for m in found {
+        match best.last_mut() {
+            Some(last) if m.start < last.end => {
+                if better(&m, last) {
+                    *last = m;
+                }
+            }
+            _ => best.push(m),
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Range;
+    use chrono::Utc;
+
+    fn annotation(id: i64, quote: Option<&str>, path: &str, start: u32, end: u32) -> Annotation {
+        let now = Utc::now();
+        Annotation {
+            id: AnnotationId(id),
+            annotator_schema_version: "v1.0".to_owned(),
+            created_at: now,
+            quote: quote.map(str::to_owned),
+            ranges: vec![Range {
+                start: Some(path.to_owned()),
+                end: Some(path.to_owned()),
+                start_offset: start,
+                end_offset: end,
+            }],
+            text: String::new(),
+            updated_at: now,
+            user: None,
+        }
+    }
+
+    const OLD: &str = "<p>The cat sat on the mat.</p>\
+        <p>The dog sat on the mat, again.</p>\
+        <p>Once more: the cat sat.</p>";
+    const NEW: &str = "<div><p>Intro.</p><p>The cat sat on the mat.</p>\
+        <p>The dog sat on the rug, again.</p>\
+        <p>Once more: the cat sat.</p></div>";
+
+    #[test]
+    fn test_reanchor_annotations() {
+        let annotations = [
+            // The second occurrence, recognised by its context.
+            annotation(1, Some("cat sat"), "/p[3]", 15, 22),
+            // Changed slightly.
+            annotation(2, Some("dog sat on the mat"), "/p[2]", 4, 22),
+            // Removed from the new content.
+            annotation(3, Some("again and again"), "/p[2]", 24, 29),
+            // No quote, found through the old content.
+            annotation(4, None, "/p[1]", 19, 22),
+        ];
+        let report = reanchor_annotations(OLD, NEW, &annotations);
+        assert!(report.unchanged.is_empty());
+        assert_eq!(
+            report.lost,
+            [LostAnnotation {
+                id: AnnotationId(3),
+                reason: LostReason::NotFound
+            }]
+        );
+
+        let new = ContentText::parse(NEW);
+        let updated: Vec<(AnnotationId, String, usize)> = report
+            .updated
+            .iter()
+            .map(|r| {
+                let anchor = new.resolve(&r.annotation).unwrap();
+                (r.annotation.id, anchor.quote, r.distance)
+            })
+            .collect();
+        assert_eq!(
+            updated,
+            [
+                (AnnotationId(1), "cat sat".to_owned(), 0),
+                (AnnotationId(2), "dog sat on the rug".to_owned(), 3),
+                (AnnotationId(4), "mat".to_owned(), 0)
+            ]
+        );
+        let moved = &report.updated[0].annotation;
+        assert_eq!(moved.ranges[0].start.as_deref(), Some("/div[1]/p[4]"));
+        assert_eq!(
+            report.updated[1].annotation.quote.as_deref(),
+            Some("dog sat on the rug")
+        );
+
+        let report = reanchor_annotations(NEW, NEW, std::slice::from_ref(moved));
+        assert_eq!(report.unchanged, [AnnotationId(1)]);
+    }
+}
@@ -0,0 +1,691 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Building EPUB books from entries. Requires the `epub` feature.
+//!
+//! `Client::build_epub` collects the entries matching a filter into an EPUB 3 book, with a table
+//! of contents and a chapter per entry. Each chapter starts with the title, authors, domain and
+//! reading time of the entry, and its preview picture if it could be downloaded. The book is
+//! built locally, so it works for any filter, unlike the exports of the server.
+//!
+//! ```no_run
+//! # async fn run(client: &mut wallabag_api::Client) -> Result<(), Box<dyn std::error::Error>> {
+//! use wallabag_api::types::EntriesFilter;
+//!
+//! let filter = EntriesFilter {
+//!     starred: Some(true),
+//!     tags: vec!["longread".to_owned()],
+//!     ..Default::default()
+//! };
+//! let book = client.build_epub("Long reads", &filter).await?;
+//! std::fs::write("longreads.epub", book)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Images in the content of entries are replaced by their alt text, because EPUB books can't
+//! refer to remote images.
+
+use std::io::{self, Cursor, Write};
+
+use chrono::{DateTime, Utc};
+use futures_util::io::AsyncReadExt;
+use log::debug;
+use scraper::node::Node;
+use scraper::{ElementRef, Html};
+use surf::http::Method;
+use surf::{Request, Url};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::errors::ClientResult;
+use crate::types::{EntriesFilter, Entry};
+use crate::utils::xml::escape;
+use crate::Client;
+
+/// Media types of images that EPUB readers must support, with the file extension used for them.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/svg+xml", "svg"),
+];
+
+/// Preview pictures larger than this, in bytes, are left out of books.
+const MAX_IMAGE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Elements that are left out of chapters, with their content.
+const DROPPED: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "object", "embed", "svg", "math", "form",
+    "input", "button", "select", "textarea", "img", "picture", "video", "audio", "canvas",
+];
+
+/// Elements without content, which are written as self-closing tags.
+const VOID: &[&str] = &["br", "hr", "wbr", "col"];
+
+/// Attributes that are kept in chapters. Everything else, like ids, classes and event handlers,
+/// is removed.
+const ATTRIBUTES: &[&str] = &[
+    "href", "title", "lang", "dir", "cite", "datetime", "colspan", "rowspan", "start",
+];
+
+const STYLESHEET: &str = "body { font-family: serif; line-height: 1.5; }
+h1 { line-height: 1.2; }
+.meta { color: #666; font-style: italic; }
+figure.preview { margin: 1em 0; text-align: center; }
+figure.preview img { max-width: 100%; }
+pre { white-space: pre-wrap; }
+";
+
+/// An image embedded in a book.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub data: Vec<u8>,
+    /// One of the image types EPUB readers support: JPEG, PNG, GIF, WebP or SVG.
+    pub media_type: String,
+}
+
+/// Builds an EPUB 3 book with a chapter per entry.
+#[derive(Debug, Clone)]
+pub struct EpubBuilder {
+    title: String,
+    language: String,
+    identifier: Option<String>,
+    modified: DateTime<Utc>,
+    chapters: Vec<(Entry, Option<Image>)>,
+}
+
+impl EpubBuilder {
+    pub fn new<T: Into<String>>(title: T) -> Self {
+        EpubBuilder {
+            title: title.into(),
+            language: "en".to_owned(),
+            identifier: None,
+            modified: Utc::now(),
+            chapters: Vec::new(),
+        }
+    }
+
+    /// Set the language of the book. The default is `en`. Chapters use the language of their
+    /// entry, if it is known.
+    pub fn with_language<T: Into<String>>(mut self, language: T) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    /// Set the unique identifier of the book. The default lists the sorted ids of the entries,
+    /// like `urn:wallabag:entries:3,14`, so the same selection of entries gets the same
+    /// identifier.
+    pub fn with_identifier<T: Into<String>>(mut self, identifier: T) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    /// Set the modification time of the book. The default is the time the builder was created.
+    pub fn with_modified(mut self, modified: DateTime<Utc>) -> Self {
+        self.modified = modified;
+        self
+    }
+
+    /// Add a chapter for an entry, with its preview picture if it was downloaded. Images of
+    /// unsupported types are left out.
+    pub fn add_entry(&mut self, entry: Entry, preview: Option<Image>) {
+        let preview = preview.filter(|image| image_extension(&image.media_type).is_some());
+        self.chapters.push((entry, preview));
+    }
+
+    /// Build the book.
+    pub fn build(&self) -> io::Result<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let deflated = SimpleFileOptions::default();
+
+        // The mimetype must be the first file, and must not be compressed.
+        zip.start_file(
+            "mimetype",
+            SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+        )?;
+        zip.write_all(b"application/epub+zip")?;
+
+        zip.start_file("META-INF/container.xml", deflated)?;
+        zip.write_all(CONTAINER.as_bytes())?;
+        zip.start_file("OEBPS/content.opf", deflated)?;
+        zip.write_all(self.package().as_bytes())?;
+        zip.start_file("OEBPS/nav.xhtml", deflated)?;
+        zip.write_all(self.navigation().as_bytes())?;
+        zip.start_file("OEBPS/toc.ncx", deflated)?;
+        zip.write_all(self.ncx().as_bytes())?;
+        zip.start_file("OEBPS/style.css", deflated)?;
+        zip.write_all(STYLESHEET.as_bytes())?;
+
+        for (index, (entry, preview)) in self.chapters.iter().enumerate() {
+            let name = chapter_name(index);
+            zip.start_file(format!("OEBPS/{}.xhtml", name), deflated)?;
+            zip.write_all(self.chapter(entry, preview.as_ref(), &name).as_bytes())?;
+            if let Some(image) = preview {
+                zip.start_file(format!("OEBPS/{}", image_path(&name, image)), deflated)?;
+                zip.write_all(&image.data)?;
+            }
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+
+    fn identifier(&self) -> String {
+        match self.identifier {
+            Some(ref identifier) => identifier.clone(),
+            None => {
+                let mut ids: Vec<_> = self.chapters.iter().map(|(entry, _)| entry.id).collect();
+                ids.sort_unstable();
+                ids.dedup();
+                let ids: Vec<_> = ids.iter().map(ToString::to_string).collect();
+                format!("urn:wallabag:entries:{}", ids.join(","))
+            }
+        }
+    }
+
+    /// The package document, which lists all files of the book.
+    fn package(&self) -> String {
+        let mut manifest = String::new();
+        let mut spine = String::new();
+        for (index, (_, preview)) in self.chapters.iter().enumerate() {
+            let name = chapter_name(index);
+            manifest.push_str(&format!(
+                "    <item id=\"{0}\" href=\"{0}.xhtml\" media-type=\"application/xhtml+xml\"/>\n",
+                name
+            ));
+            if let Some(image) = preview {
+                manifest.push_str(&format!(
+                    "    <item id=\"{}-preview\" href=\"{}\" media-type=\"{}\"/>\n",
+                    name,
+                    image_path(&name, image),
+                    escape(&image.media_type)
+                ));
+            }
+            spine.push_str(&format!("    <itemref idref=\"{}\"/>\n", name));
+        }
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">{}</dc:identifier>
+    <dc:title>{}</dc:title>
+    <dc:language>{}</dc:language>
+    <meta property="dcterms:modified">{}</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+    <item id="style" href="style.css" media-type="text/css"/>
+{}  </manifest>
+  <spine toc="ncx">
+    <itemref idref="nav"/>
+{}  </spine>
+</package>
+"#,
+            escape(&self.identifier()),
+            escape(&self.title),
+            escape(&self.language),
+            self.modified.format("%Y-%m-%dT%H:%M:%SZ"),
+            manifest,
+            spine
+        )
+    }
+
+    /// The table of contents of EPUB 3.
+    fn navigation(&self) -> String {
+        let mut items = String::new();
+        for (index, (entry, _)) in self.chapters.iter().enumerate() {
+            items.push_str(&format!(
+                "      <li><a href=\"{}.xhtml\">{}</a></li>\n",
+                chapter_name(index),
+                escape(&entry.display_title())
+            ));
+        }
+        format!(
+            r#"{}
+  <nav epub:type="toc" id="toc">
+    <h1>{}</h1>
+    <ol>
+{}    </ol>
+  </nav>
+</body>
+</html>
+"#,
+            xhtml_head(&self.title, &self.language),
+            escape(&self.title),
+            items
+        )
+    }
+
+    /// The table of contents of EPUB 2, for older readers.
+    fn ncx(&self) -> String {
+        let mut points = String::new();
+        for (index, (entry, _)) in self.chapters.iter().enumerate() {
+            points.push_str(&format!(
+                r#"    <navPoint id="{0}" playOrder="{1}">
+      <navLabel><text>{2}</text></navLabel>
+      <content src="{0}.xhtml"/>
+    </navPoint>
+"#,
+                chapter_name(index),
+                index + 1,
+                escape(&entry.display_title())
+            ));
+        }
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <head>
+    <meta name="dtb:uid" content="{}"/>
+  </head>
+  <docTitle><text>{}</text></docTitle>
+  <navMap>
+{}  </navMap>
+</ncx>
+"#,
+            escape(&self.identifier()),
+            escape(&self.title),
+            points
+        )
+    }
+
+    fn chapter(&self, entry: &Entry, preview: Option<&Image>, name: &str) -> String {
+        let language = entry
+            .language
+            .as_deref()
+            .and_then(language_tag)
+            .unwrap_or_else(|| self.language.clone());
+        let title = entry.display_title();
+
+        let mut meta = Vec::new();
+        if let Some(ref authors) = entry.published_by {
+            if !authors.is_empty() {
+                meta.push(format!("By {}", authors));
+            }
+        }
+        if let Some(ref domain) = entry.domain_name {
+            meta.push(domain.clone());
+        }
+        if entry.reading_time > 0 {
+            meta.push(format!("{} min read", entry.reading_time));
+        }
+
+        let mut body = format!("  <h1>{}</h1>\n", escape(&title));
+        if !meta.is_empty() {
+            body.push_str(&format!(
+                "  <p class=\"meta\">{}</p>\n",
+                escape(&meta.join(" · "))
+            ));
+        }
+        if let Some(image) = preview {
+            body.push_str(&format!(
+                "  <figure class=\"preview\"><img src=\"{}\" alt=\"\"/></figure>\n",
+                image_path(name, image)
+            ));
+        }
+        let base_url = entry.url.as_deref().and_then(|url| Url::parse(url).ok());
+        body.push_str(&format!(
+            "  <div class=\"content\">{}</div>\n",
+            to_xhtml(
+                entry.content.as_deref().unwrap_or_default(),
+                base_url.as_ref()
+            )
+        ));
+        format!(
+            "{}\n{}</body>\n</html>\n",
+            xhtml_head(&title, &language),
+            body
+        )
+    }
+}
+
+impl Client {
+    /// Build an EPUB book from the entries matching the filter, in the order they are returned.
+    /// Preview pictures are downloaded and embedded. Pictures that can't be downloaded, have an
+    /// unsupported type or are larger than 5 MiB are left out. They are downloaded without the
+    /// middleware of the client, which is only applied to requests to the wallabag server.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn build_epub(
+        &mut self,
+        title: &str,
+        filter: &EntriesFilter,
+    ) -> ClientResult<Vec<u8>> {
+        let mut builder = EpubBuilder::new(title);
+        for entry in self.get_entries_with_filter(filter).await? {
+            let preview = match entry.preview_picture {
+                Some(ref url) => self.download_image(url).await,
+                None => None,
+            };
+            builder.add_entry(entry, preview);
+        }
+        Ok(builder.build()?)
+    }
+
+    async fn download_image(&self, url: &str) -> Option<Image> {
+        let url = Url::parse(url).ok()?;
+        let mut response = match self
+            .send_external(Request::new(Method::Get, url.clone()))
+            .await
+        {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                debug!(
+                    "Downloading {} failed with status {}",
+                    url,
+                    response.status()
+                );
+                return None;
+            }
+            Err(err) => {
+                debug!("Downloading {} failed: {}", url, err);
+                return None;
+            }
+        };
+        let media_type = response.content_type()?.essence().to_owned();
+        if image_extension(&media_type).is_none() {
+            debug!("Image {} has unsupported type {}", url, media_type);
+            return None;
+        }
+        if response
+            .len()
+            .is_some_and(|len| len as u64 > MAX_IMAGE_SIZE)
+        {
+            debug!("Image {} is too large", url);
+            return None;
+        }
+        // The length is only a hint, so the body is read up to the limit as well.
+        let mut data = Vec::new();
+        response
+            .take_body()
+            .take(MAX_IMAGE_SIZE + 1)
+            .read_to_end(&mut data)
+            .await
+            .ok()?;
+        if data.len() as u64 > MAX_IMAGE_SIZE {
+            debug!("Image {} is too large", url);
+            return None;
+        }
+        Some(Image { data, media_type })
+    }
+}
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// The start of an XHTML document, up to and including the body tag.
+fn xhtml_head(title: &str, language: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" lang="{0}" xml:lang="{0}">
+<head>
+  <title>{1}</title>
+  <link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>"#,
+        escape(language),
+        escape(title)
+    )
+}
+
+fn chapter_name(index: usize) -> String {
+    format!("chapter-{}", index + 1)
+}
+
+fn image_extension(media_type: &str) -> Option<&'static str> {
+    IMAGE_TYPES
+        .iter()
+        .find(|(known, _)| *known == media_type)
+        .map(|(_, extension)| *extension)
+}
+
+/// Path of the preview picture of a chapter, relative to the package document.
+fn image_path(chapter: &str, image: &Image) -> String {
+    format!(
+        "images/{}.{}",
+        chapter,
+        image_extension(&image.media_type).unwrap_or("img")
+    )
+}
+
+/// Wallabag stores languages like `en_US`, EPUB expects language tags like `en-US`.
+fn language_tag(language: &str) -> Option<String> {
+    let tag = language.trim().replace('_', "-");
+    if !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        Some(tag)
+    } else {
+        None
+    }
+}
+
+/// Convert HTML to well-formed XHTML, which EPUB requires. Relative links are resolved against
+/// `base_url`, and links that can't be resolved are removed.
+fn to_xhtml(html: &str, base_url: Option<&Url>) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut out = String::new();
+    write_children(&mut out, fragment.root_element(), base_url);
+    out
+}
+
+fn write_children(out: &mut String, element: ElementRef, base_url: Option<&Url>) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(&escape(text)),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    write_element(out, child, base_url);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn write_element(out: &mut String, element: ElementRef, base_url: Option<&Url>) {
+    let name = element.value().name().to_lowercase();
+    if name == "img" {
+        if let Some(alt) = element.value().attr("alt") {
+            out.push_str(&escape(alt));
+        }
+        return;
+    }
+    if DROPPED.contains(&name.as_str()) {
+        return;
+    }
+    // Unknown elements with names that aren't valid in XML, eg. with a namespace prefix.
+    let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid_name {
+        write_children(out, element, base_url);
+        return;
+    }
+
+    out.push('<');
+    out.push_str(&name);
+    for (attribute, value) in element.value().attrs() {
+        let attribute = attribute.to_lowercase();
+        if !ATTRIBUTES.contains(&attribute.as_str()) {
+            continue;
+        }
+        let value = if attribute == "href" {
+            match resolve_link(value, base_url) {
+                Some(link) => link,
+                None => continue,
+            }
+        } else {
+            value.to_owned()
+        };
+        out.push_str(&format!(" {}=\"{}\"", attribute, escape(&value)));
+    }
+    if VOID.contains(&name.as_str()) {
+        out.push_str("/>");
+        return;
+    }
+    out.push('>');
+    write_children(out, element, base_url);
+    out.push_str(&format!("</{}>", name));
+}
+
+/// An absolute http(s) or mailto link. Links within the page are dropped, because the ids they
+/// point to are removed.
+fn resolve_link(href: &str, base_url: Option<&Url>) -> Option<String> {
+    let href = href.trim();
+    if href.starts_with('#') {
+        return None;
+    }
+    let url = match base_url {
+        Some(base) => base.join(href).ok()?,
+        None => Url::parse(href).ok()?,
+    };
+    if matches!(url.scheme(), "http" | "https" | "mailto") {
+        Some(url.into())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::{Middleware, Next};
+    use crate::testing::MockServer;
+    use crate::types::NewEntry;
+    use async_std::task::block_on;
+    use async_trait::async_trait;
+    use std::io::Read;
+    use std::sync::{Arc, Mutex};
+    use surf::http::{self, StatusCode};
+    use surf::Response;
+    use zip::ZipArchive;
+
+    fn read(book: &[u8], name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(book)).unwrap();
+        let mut file = archive.by_name(name).unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+        contents
+    }
+
+    #[test]
+    fn test_to_xhtml() {
+        let base = Url::parse("https://example.com/posts/1").unwrap();
+        assert_eq!(
+            to_xhtml(
+                r##"<p id="x" onclick="evil()">A<br>B &amp; <a href="../2">next</a>
+                <a href="#top">top</a><img src="cat.jpg" alt="A cat"></p><script>x</script>"##,
+                Some(&base)
+            ),
+            "<p>A<br/>B &amp; <a href=\"https://example.com/2\">next</a>\n                \
+             <a>top</a>A cat</p>"
+        );
+    }
+
+    #[test]
+    fn test_build_epub() {
+        block_on(async {
+            let server = MockServer::new();
+            server.respond(
+                "/cover.png",
+                StatusCode::Ok,
+                Some(http::mime::PNG),
+                b"\x89PNG\r\n",
+            );
+            let mut client = server.client();
+            let mut new_entry = NewEntry::new_with_url("https://example.com/long".to_owned());
+            new_entry.title = Some("A <long> read".to_owned());
+            new_entry.content = Some(format!("<p>Once upon a time.{}</p>", " Word".repeat(200)));
+            new_entry.authors = Some("Ada Lovelace".into());
+            new_entry.language = Some("en_GB".to_owned());
+            new_entry.preview_picture = Some("https://images.example.com/cover.png".to_owned());
+            new_entry.tags = Some(vec!["longread".to_owned()]);
+            let entry = client.create_entry(&new_entry).await.unwrap();
+            server.add_entry("https://example.com/short", &[]);
+
+            let filter = EntriesFilter {
+                tags: vec!["longread".to_owned()],
+                ..Default::default()
+            };
+            let book = client.build_epub("Long reads", &filter).await.unwrap();
+            assert_eq!(&book[30..38], b"mimetype");
+            assert_eq!(read(&book, "mimetype"), "application/epub+zip");
+
+            let package = read(&book, "OEBPS/content.opf");
+            assert!(package.contains("<dc:title>Long reads</dc:title>"));
+            assert!(package.contains(&format!(
+                "<dc:identifier id=\"book-id\">urn:wallabag:entries:{}</dc:identifier>",
+                entry.id
+            )));
+            assert!(package.contains(
+                "<item id=\"chapter-1-preview\" href=\"images/chapter-1.png\" \
+                 media-type=\"image/png\"/>"
+            ));
+            assert!(!package.contains("chapter-2"));
+
+            let nav = read(&book, "OEBPS/nav.xhtml");
+            assert!(nav.contains("<a href=\"chapter-1.xhtml\">A &lt;long&gt; read</a>"));
+
+            let chapter = read(&book, "OEBPS/chapter-1.xhtml");
+            assert!(chapter.contains("lang=\"en-GB\""));
+            assert!(chapter
+                .contains("<p class=\"meta\">By Ada Lovelace · example.com · 1 min read</p>"));
+            assert!(chapter.contains("<img src=\"images/chapter-1.png\" alt=\"\"/>"));
+            assert!(chapter.contains("<p>Once upon a time. Word"));
+        })
+    }
+
+    /// Records the host of every request that passes the middleware of the client.
+    struct Hosts(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl Middleware for Hosts {
+        async fn handle(
+            &self,
+            req: Request,
+            client: surf::Client,
+            next: Next<'_>,
+        ) -> surf::Result<Response> {
+            let host = req.url().host_str().unwrap_or_default().to_owned();
+            self.0.lock().unwrap().push(host);
+            next.run(req, client).await
+        }
+    }
+
+    #[test]
+    fn test_download_images() {
+        block_on(async {
+            let server = MockServer::new();
+            let png = Some(http::mime::PNG);
+            server.respond("/small.png", StatusCode::Ok, png.clone(), b"\x89PNG\r\n");
+            let huge = vec![0; MAX_IMAGE_SIZE as usize + 1];
+            server.respond("/huge.png", StatusCode::Ok, png, &huge);
+            let hosts = Arc::new(Mutex::new(Vec::new()));
+            let mut client = server.client().with_middleware(Hosts(hosts.clone()));
+            for name in ["small", "huge"] {
+                let mut new_entry = NewEntry::new_with_url(format!("https://example.com/{}", name));
+                new_entry.preview_picture =
+                    Some(format!("https://images.example.com/{}.png", name));
+                client.create_entry(&new_entry).await.unwrap();
+            }
+
+            let book = client
+                .build_epub("Pictures", &EntriesFilter::default())
+                .await
+                .unwrap();
+            let package = read(&book, "OEBPS/content.opf");
+            assert_eq!(package.matches("-preview\"").count(), 1);
+
+            // Both pictures were requested, but not through the middleware of the client.
+            let requests = server.requests();
+            assert!(requests.contains(&"GET /small.png".to_owned()));
+            assert!(requests.contains(&"GET /huge.png".to_owned()));
+            let hosts = hosts.lock().unwrap();
+            assert!(!hosts.is_empty());
+            assert!(hosts.iter().all(|host| host == "wallabag.test"));
+        })
+    }
+}
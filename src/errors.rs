@@ -4,75 +4,699 @@
 
 //! Client error types.
 
+use std::fmt;
+
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
+use serde_json::Value;
 use serde_urlencoded;
-use surf::http::StatusCode;
+use surf::http::{Method, StatusCode};
 use surf::{self, http::url};
 use thiserror::Error;
 
+use crate::types::Format;
+
 pub type ClientResult<T> = std::result::Result<T, ClientError>;
 
 /// Type for the JSON error data returned on error from the HTTP API
 /// The API seems a bit unreliable on the format of errors returned...
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ResponseError {
     pub error: String,
     pub error_description: String,
 }
 
 /// Type for the JSON error data returned on forbidden error from HTTP API
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ResponseCodeMessageError {
     pub error: CodeMessage,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct CodeMessage {
     pub code: u32,
     pub message: String,
 }
 
+/// Error details parsed from the body of a 400, 409, 422 or 500 response. Wallabag uses a few
+/// different formats, so all fields are optional.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiError {
+    /// The code in the body, usually equal to the HTTP status.
+    pub code: Option<u32>,
+    /// Short error identifier, eg. `invalid_request` for oauth errors.
+    pub error: Option<String>,
+    /// Human readable message.
+    pub message: Option<String>,
+    /// Further details, eg. the validation errors per field of a form.
+    pub details: Option<Value>,
+}
+
+/// The formats of error bodies returned by wallabag.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ApiErrorBody {
+    /// `{"error": {"code": 400, "message": "..."}}`
+    Wrapped { error: CodeMessage },
+    /// `{"error": "invalid_request", "error_description": "..."}`
+    OAuth {
+        error: String,
+        error_description: Option<String>,
+    },
+    /// `{"code": 400, "message": "Validation Failed", "errors": {...}}`
+    Flat {
+        code: Option<u32>,
+        message: String,
+        errors: Option<Value>,
+    },
+}
+
+impl ApiError {
+    /// Parse an error body. Returns `None` if the body isn't in one of the known formats.
+    pub fn from_body(body: &str) -> Option<Self> {
+        let parsed = serde_json::from_str::<ApiErrorBody>(body).ok()?;
+        Some(match parsed {
+            ApiErrorBody::Wrapped { error } => ApiError {
+                code: Some(error.code),
+                message: Some(error.message),
+                ..Default::default()
+            },
+            ApiErrorBody::OAuth {
+                error,
+                error_description,
+            } => ApiError {
+                error: Some(error),
+                message: error_description,
+                ..Default::default()
+            },
+            ApiErrorBody::Flat {
+                code,
+                message,
+                errors,
+            } => ApiError {
+                code,
+                message: Some(message),
+                details: errors,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.error, &self.message) {
+            (Some(error), Some(message)) => write!(f, "{}: {}", error, message),
+            (Some(error), None) => write!(f, "{}", error),
+            (None, Some(message)) => write!(f, "{}", message),
+            (None, None) => write!(f, "no details"),
+        }
+    }
+}
+
+/// The request that caused an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestContext {
+    pub method: Method,
+    /// Name of the endpoint without ids, eg. `entry` or `token`.
+    pub endpoint: &'static str,
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.endpoint)
+    }
+}
+
 /// Represents all error possibilities that could be returned by the client.
-#[derive(Error, Debug)]
+///
+/// Errors caused by a response of the server, or by failing to reach it, carry the
+/// `RequestContext` of the request. Errors caused by a response also carry its status and raw
+/// body, even if the body couldn't be parsed. Use the helper methods like `is_retryable` and
+/// `status` instead of matching on variants where possible.
+#[derive(Debug)]
 pub enum ClientError {
-    #[error("HTTP operation failed")]
-    SurfError(surf::Error),
-    #[error("Error deserializing json")]
-    SerdeJsonError(#[from] serde_json::error::Error),
-    #[error("Unauthorized")]
-    Unauthorized(ResponseError),
-    #[error("Forbidden")]
-    Forbidden(ResponseCodeMessageError),
-    #[error("Token is expired")]
-    ExpiredToken,
-    #[error("IO error")]
-    IOError(#[from] std::io::Error),
-    #[error("URL parse error")]
-    UrlParseError(#[from] url::ParseError),
-    #[error("URL encode error")]
-    UrlEncodeError(#[from] serde_urlencoded::ser::Error),
-    #[error("Unexpected JSON structure, eg returned valid json but didn't fit model")]
+    /// The request couldn't be built, eg. because its query or body couldn't be encoded.
+    InvalidRequest {
+        context: RequestContext,
+        error: surf::Error,
+    },
+    /// Sending the request or reading the response failed, eg. because the connection failed.
+    Transport {
+        context: RequestContext,
+        error: surf::Error,
+    },
+    /// The body of a successful response isn't what was expected.
+    Decode {
+        context: RequestContext,
+        error: DecodeError,
+    },
+    /// Data other than a response couldn't be encoded or decoded as JSON.
+    SerdeJsonError(serde_json::error::Error),
+    /// The credentials or the token were rejected (401).
+    Unauthorized {
+        context: RequestContext,
+        status: StatusCode,
+        body: String,
+        /// The parsed body, if it was in the expected format.
+        info: Option<ResponseError>,
+    },
+    /// The user isn't allowed to access the resource (403).
+    Forbidden {
+        context: RequestContext,
+        status: StatusCode,
+        body: String,
+        /// The parsed body, if it was in the expected format.
+        info: Option<ResponseCodeMessageError>,
+    },
+    /// The access token expired (401). The client refreshes it and retries once by itself.
+    ExpiredToken {
+        context: RequestContext,
+        status: StatusCode,
+        body: String,
+    },
+    /// Reading or writing local data failed, eg. while writing an export.
+    IOError(std::io::Error),
+    /// The url of a request is invalid, eg. because the base url of the config is.
+    UrlParseError(url::ParseError),
+    /// Data couldn't be encoded as a form or query string.
+    UrlEncodeError(serde_urlencoded::ser::Error),
+    /// A response is valid JSON, but doesn't fit the model.
     UnexpectedJsonStructure,
-    #[error("Resource not found")]
-    NotFound(ResponseCodeMessageError),
-    #[error("Resource not modified")]
-    NotModified,
-    #[error("Unknown status code")]
-    Other(StatusCode, String),
+    /// The request wasn't sent, because the data in it is invalid. See `validate::Validate`.
+    Validation(ValidationError),
+    /// An export couldn't be decoded. See the `export` module.
+    Export(ExportError),
+    /// The resource doesn't exist (404).
+    NotFound {
+        context: RequestContext,
+        status: StatusCode,
+        body: String,
+        /// The parsed body, if it was in the expected format.
+        info: Option<ResponseCodeMessageError>,
+    },
+    /// The resource didn't change (304).
+    NotModified {
+        context: RequestContext,
+        status: StatusCode,
+        body: String,
+    },
+    /// The server rejected the request (400, 409 or 422) or failed to handle it (500), and
+    /// explained why.
+    Api {
+        context: RequestContext,
+        status: StatusCode,
+        body: String,
+        error: ApiError,
+    },
+    /// Any other status that isn't a success.
+    Other {
+        context: RequestContext,
+        status: StatusCode,
+        body: String,
+    },
 }
 
-// TODO: extract surf errors and turn them into more useful ClientErrors
-// TODO: maybe impl Error::cause to get the underlying surf or serde errors?
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::InvalidRequest { context, .. } => {
+                write!(f, "Building request failed ({})", context)
+            }
+            ClientError::Transport { context, .. } => {
+                write!(f, "Sending request failed ({})", context)
+            }
+            ClientError::Decode { context, .. } => {
+                write!(f, "Decoding response failed ({})", context)
+            }
+            ClientError::SerdeJsonError(_) => write!(f, "Error deserializing json"),
+            ClientError::Unauthorized { context, .. } => write!(f, "Unauthorized ({})", context),
+            ClientError::Forbidden { context, .. } => write!(f, "Forbidden ({})", context),
+            ClientError::ExpiredToken { context, .. } => {
+                write!(f, "Token is expired ({})", context)
+            }
+            ClientError::IOError(_) => write!(f, "IO error"),
+            ClientError::UrlParseError(_) => write!(f, "URL parse error"),
+            ClientError::UrlEncodeError(_) => write!(f, "URL encode error"),
+            ClientError::UnexpectedJsonStructure => write!(
+                f,
+                "Unexpected JSON structure, eg returned valid json but didn't fit model"
+            ),
+            ClientError::Validation(_) => write!(f, "Invalid request data"),
+            ClientError::Export(_) => write!(f, "Decoding export failed"),
+            ClientError::NotFound { context, .. } => {
+                write!(f, "Resource not found ({})", context)
+            }
+            ClientError::NotModified { context, .. } => {
+                write!(f, "Resource not modified ({})", context)
+            }
+            ClientError::Api {
+                context,
+                status,
+                error,
+                ..
+            } => write!(
+                f,
+                "Request failed with status {} ({}): {}",
+                status, context, error
+            ),
+            ClientError::Other {
+                context, status, ..
+            } => write!(f, "Unknown status code {} ({})", status, context),
+        }
+    }
+}
 
-impl From<surf::Error> for ClientError {
-    fn from(err: surf::Error) -> Self {
-        ClientError::SurfError(err)
+/// Implemented by hand, because `surf::Error` doesn't implement `std::error::Error` itself.
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::InvalidRequest { error, .. } | ClientError::Transport { error, .. } => {
+                Some(error.as_ref())
+            }
+            ClientError::Decode { error, .. } => Some(error),
+            ClientError::SerdeJsonError(e) => Some(e),
+            ClientError::IOError(e) => Some(e),
+            ClientError::UrlParseError(e) => Some(e),
+            ClientError::UrlEncodeError(e) => Some(e),
+            ClientError::Validation(e) => Some(e),
+            ClientError::Export(e) => Some(e),
+            _ => None,
+        }
     }
 }
 
-/// Represents possible errors building a `TagString`.
+impl ClientError {
+    /// The HTTP status of the response that caused this error, if the server responded at all.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Unauthorized { status, .. }
+            | ClientError::Forbidden { status, .. }
+            | ClientError::ExpiredToken { status, .. }
+            | ClientError::NotFound { status, .. }
+            | ClientError::NotModified { status, .. }
+            | ClientError::Api { status, .. }
+            | ClientError::Other { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// The raw body of the response that caused this error, if the server responded at all.
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            ClientError::Unauthorized { body, .. }
+            | ClientError::Forbidden { body, .. }
+            | ClientError::ExpiredToken { body, .. }
+            | ClientError::NotFound { body, .. }
+            | ClientError::NotModified { body, .. }
+            | ClientError::Api { body, .. }
+            | ClientError::Other { body, .. } => Some(body),
+            _ => None,
+        }
+    }
+
+    /// The request that caused this error, if the error came from sending a request.
+    pub fn context(&self) -> Option<&RequestContext> {
+        match self {
+            ClientError::InvalidRequest { context, .. }
+            | ClientError::Transport { context, .. }
+            | ClientError::Decode { context, .. }
+            | ClientError::Unauthorized { context, .. }
+            | ClientError::Forbidden { context, .. }
+            | ClientError::ExpiredToken { context, .. }
+            | ClientError::NotFound { context, .. }
+            | ClientError::NotModified { context, .. }
+            | ClientError::Api { context, .. }
+            | ClientError::Other { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again later might succeed. True for network failures,
+    /// timeouts, rate limiting and server errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::Transport { .. } => true,
+            _ => match self.status() {
+                Some(StatusCode::RequestTimeout) | Some(StatusCode::TooManyRequests) => true,
+                Some(StatusCode::NotImplemented) => false,
+                Some(status) => status.is_server_error(),
+                None => false,
+            },
+        }
+    }
+
+    /// Whether the credentials or token were rejected, or the user isn't allowed to access the
+    /// resource.
+    pub fn is_auth(&self) -> bool {
+        matches!(
+            self,
+            ClientError::Unauthorized { .. }
+                | ClientError::ExpiredToken { .. }
+                | ClientError::Forbidden { .. }
+        )
+    }
+
+    /// Whether the resource doesn't exist (anymore), eg. because the entry was deleted.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, ClientError::NotFound { .. })
+    }
+}
+
+impl From<serde_json::error::Error> for ClientError {
+    fn from(err: serde_json::error::Error) -> Self {
+        ClientError::SerdeJsonError(err)
+    }
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(err: std::io::Error) -> Self {
+        ClientError::IOError(err)
+    }
+}
+
+impl From<url::ParseError> for ClientError {
+    fn from(err: url::ParseError) -> Self {
+        ClientError::UrlParseError(err)
+    }
+}
+
+impl From<serde_urlencoded::ser::Error> for ClientError {
+    fn from(err: serde_urlencoded::ser::Error) -> Self {
+        ClientError::UrlEncodeError(err)
+    }
+}
+
+impl From<ValidationError> for ClientError {
+    fn from(err: ValidationError) -> Self {
+        ClientError::Validation(err)
+    }
+}
+
+impl From<ExportError> for ClientError {
+    fn from(err: ExportError) -> Self {
+        ClientError::Export(err)
+    }
+}
+
+/// Represents the reasons the body of a successful response can't be decoded.
 #[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("Response is not valid UTF-8")]
+    Utf8(#[from] std::string::FromUtf8Error),
+    #[error("Response is not the expected JSON")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Represents the reasons data is rejected before it is sent to the server.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("Quote of an annotation must not be empty")]
+    EmptyQuote,
+    #[error("Annotation must cover at least one range")]
+    NoRanges,
+    #[error("Range {index} is invalid: {reason}")]
+    InvalidRange { index: usize, reason: &'static str },
+    #[error("Field {field} must be an absolute http(s) url, got {url:?}")]
+    InvalidUrl { field: &'static str, url: String },
+    #[error("Tag {label:?} contains a comma")]
+    TagContainsComma { label: String },
+    #[error("Author {name:?} contains a comma")]
+    AuthorContainsComma { name: String },
+    #[error("Published date {published_at} is in the future")]
+    PublishedInFuture { published_at: DateTime<Utc> },
+}
+
+/// Represents the reasons an annotation range can't be mapped to the content of an entry.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AnchorError {
+    #[error("Entry has no content")]
+    NoContent,
+    #[error("Annotation has no ranges")]
+    NoRanges,
+    #[error("Range has no start or end path")]
+    MissingPath,
+    #[error("Path {path:?} does not match an element of the content")]
+    PathNotFound { path: String },
+    #[error("Offset {offset} is outside of the text of {path:?}")]
+    OffsetOutOfRange { path: String, offset: u32 },
+    #[error("Range ends before it starts")]
+    Reversed,
+    #[error("Selection {start}..{end} is not within the {len} characters of the content")]
+    InvalidSelection {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+}
+
+/// Represents the reasons an export of entries can't be decoded or written.
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Exports in {0} format can't be decoded")]
+    UnsupportedFormat(Format),
+    /// The response isn't an export in the requested format, eg. an HTML error page.
+    #[error("Expected a {format} export, got content type {content_type:?}")]
+    ContentType {
+        format: Format,
+        content_type: Option<String>,
+    },
+    #[error("Invalid JSON export")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid CSV export: {0}")]
+    Csv(String),
+    #[error("Invalid XML export")]
+    Xml(#[from] quick_xml::Error),
+}
+
+/// Represents the reasons a file exported by another service can't be imported.
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Invalid JSON")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid CSV: {0}")]
+    Csv(String),
+    #[error("Column {0} is missing")]
+    MissingColumn(&'static str),
+}
+
+/// Represents possible errors building a `TagString`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum TagStringError {
     #[error("Contains comma (invalid character)")]
     ContainsComma,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::{Middleware, Next};
+    use crate::testing::MockServer;
+    use async_std::task::block_on;
+    use async_trait::async_trait;
+    use surf::{http, Body, Request, Response};
+
+    #[test]
+    fn test_api_error_formats() {
+        let wrapped =
+            ApiError::from_body(r#"{"error":{"code":500,"message":"Internal Server Error"}}"#)
+                .unwrap();
+        assert_eq!(wrapped.code, Some(500));
+        assert_eq!(wrapped.message.as_deref(), Some("Internal Server Error"));
+
+        let oauth = ApiError::from_body(
+            r#"{"error":"invalid_request","error_description":"Invalid grant_type parameter"}"#,
+        )
+        .unwrap();
+        assert_eq!(oauth.error.as_deref(), Some("invalid_request"));
+        assert_eq!(
+            oauth.to_string(),
+            "invalid_request: Invalid grant_type parameter"
+        );
+
+        let validation = ApiError::from_body(
+            r#"{"code":400,"message":"Validation Failed","errors":{"children":{"url":{"errors":["This value should not be blank."]}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(validation.code, Some(400));
+        assert!(validation.details.unwrap()["children"]["url"].is_object());
+
+        assert_eq!(ApiError::from_body("<html>Bad Gateway</html>"), None);
+    }
+
+    #[test]
+    fn test_error_taxonomy() {
+        block_on(async {
+            let server = MockServer::new();
+            server.respond(
+                "/api/tags.json",
+                StatusCode::Conflict,
+                None,
+                br#"{"error":{"code":409,"message":"Tag is in use"}}"#,
+            );
+            let mut client = server.client();
+            let err = client.get_tags().await.unwrap_err();
+            assert_eq!(err.status(), Some(StatusCode::Conflict));
+            assert_eq!(
+                err.context(),
+                Some(&RequestContext {
+                    method: Method::Get,
+                    endpoint: "tags"
+                })
+            );
+            assert!(!err.is_retryable());
+            assert!(!err.is_auth());
+            match err {
+                ClientError::Api { error, .. } => {
+                    assert_eq!(error.message.as_deref(), Some("Tag is in use"))
+                }
+                e => panic!("unexpected error {:?}", e),
+            }
+
+            server.respond(
+                "/api/tags.json",
+                StatusCode::BadGateway,
+                None,
+                b"<html>Bad Gateway</html>",
+            );
+            let mut client = server.client();
+            let err = client.get_tags().await.unwrap_err();
+            assert!(err.is_retryable());
+            assert!(matches!(err, ClientError::Other { .. }));
+
+            let mut client = server.client();
+            let err = client.get_entry(1).await.unwrap_err();
+            assert!(err.is_not_found());
+            assert_eq!(err.context().unwrap().to_string(), "GET entry");
+        })
+    }
+
+    #[test]
+    fn test_undecodable_body_is_not_retryable() {
+        block_on(async {
+            let server = MockServer::new();
+            let context = RequestContext {
+                method: Method::Get,
+                endpoint: "tags",
+            };
+            server.respond(
+                "/api/tags.json",
+                StatusCode::Ok,
+                None,
+                br#"{"unexpected": true}"#,
+            );
+            let mut client = server.client();
+            let err = client.get_tags().await.unwrap_err();
+            assert!(matches!(
+                err,
+                ClientError::Decode {
+                    error: DecodeError::Json(_),
+                    ..
+                }
+            ));
+            assert_eq!(err.context(), Some(&context));
+            assert!(!err.is_retryable());
+
+            server.respond("/api/tags.json", StatusCode::Ok, None, b"[\"\xff\"]");
+            let mut client = server.client();
+            let err = client.get_tags().await.unwrap_err();
+            assert!(matches!(
+                err,
+                ClientError::Decode {
+                    error: DecodeError::Utf8(_),
+                    ..
+                }
+            ));
+            assert_eq!(err.context(), Some(&context));
+            assert!(!err.is_retryable());
+        })
+    }
+
+    /// A response body that fails while it is read, like a connection that is reset.
+    struct ResetBody;
+
+    impl futures_util::io::AsyncRead for ResetBody {
+        fn poll_read(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            _buf: &mut [u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            std::task::Poll::Ready(Err(std::io::ErrorKind::ConnectionReset.into()))
+        }
+    }
+
+    /// Answers all requests for tags with a body that can't be read.
+    struct ResetTags;
+
+    #[async_trait]
+    impl Middleware for ResetTags {
+        async fn handle(
+            &self,
+            req: Request,
+            client: surf::Client,
+            next: Next<'_>,
+        ) -> surf::Result<Response> {
+            if req.url().path() == "/api/tags.json" {
+                let mut res = http::Response::new(StatusCode::Ok);
+                let reader = futures_util::io::BufReader::new(ResetBody);
+                res.set_body(Body::from_reader(reader, None));
+                return Ok(res.into());
+            }
+            next.run(req, client).await
+        }
+    }
+
+    #[test]
+    fn test_body_read_failure_is_retryable() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client().with_middleware(ResetTags);
+            let err = client.get_tags().await.unwrap_err();
+            assert!(matches!(err, ClientError::Transport { .. }));
+            assert_eq!(err.context().unwrap().to_string(), "GET tags");
+            assert!(err.is_retryable());
+        })
+    }
+
+    #[test]
+    fn test_unexpected_error_bodies_keep_status() {
+        block_on(async {
+            let server = MockServer::new();
+            for status in [StatusCode::Unauthorized, StatusCode::Forbidden] {
+                server.respond(
+                    "/api/tags.json",
+                    status,
+                    None,
+                    b"<html>Proxy says no</html>",
+                );
+                let mut client = server.client();
+                let err = client.get_tags().await.unwrap_err();
+                assert_eq!(err.status(), Some(status));
+                assert_eq!(err.body(), Some("<html>Proxy says no</html>"));
+                assert!(err.is_auth());
+                match err {
+                    ClientError::Unauthorized { info, .. } => assert!(info.is_none()),
+                    ClientError::Forbidden { info, .. } => assert!(info.is_none()),
+                    e => panic!("unexpected error {:?}", e),
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_error_source_chain() {
+        block_on(async {
+            let server = MockServer::new();
+            server.set_offline(true);
+            let mut client = server.client();
+            let err = client.get_tags().await.unwrap_err();
+            assert_eq!(err.to_string(), "Sending request failed (POST token)");
+            assert_eq!(err.status(), None);
+            let source = std::error::Error::source(&err).unwrap();
+            assert_eq!(source.to_string(), "network is down");
+
+            let err = ClientError::from(serde_json::from_str::<u32>("x").unwrap_err());
+            assert!(std::error::Error::source(&err).is_some());
+        })
+    }
+}
@@ -0,0 +1,693 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Decoding of entry exports.
+//!
+//! `Client::export_entry` returns the export as text, which corrupts the binary formats (PDF,
+//! EPUB and MOBI). Use `Client::export_entry_bytes` for those. The JSON, CSV and XML formats can
+//! be decoded to `ExportedEntry` records with `decode`, or fetched and decoded at once with
+//! `Client::export_entry_records`. Large exports can be written to a file while they are received
+//! with `Client::export_entry_to`.
+//!
+//! `Client::export_entries_to` exports all entries matching a filter at once. It uses the export
+//! of the web interface if the server allows it, and combines the entries into a JSON or CSV
+//! export itself otherwise. The combined export has the same fields as the export of the server,
+//! not the entries of the API.
+//!
+//! ```
+//! use wallabag_api::export::decode;
+//! use wallabag_api::types::Format;
+//!
+//! let csv = "Title;URL;Content;Tags;MIME Type;Language;Creation date\n\
+//!            \"An article\";https://example.com/;\"<p>Text</p>\";\"rust, news\";text/html;en;\
+//!            04/03/2017 10:33:49\n";
+//! let entries = decode(Format::CSV, csv).unwrap();
+//! assert_eq!(entries[0].title.as_deref(), Some("An article"));
+//! assert_eq!(entries[0].tags, ["rust", "news"]);
+//! ```
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::errors::ExportError;
+use crate::types::{Authors, EntriesFilter, Entry, EntryId, Format};
+
+/// An entry as decoded from an export.
+///
+/// Exports contain a subset of the fields of `Entry`, which differs per format, so all fields
+/// are optional. The CSV format only has the title, url, content, tags, mime type, language and
+/// creation date.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExportedEntry {
+    pub id: Option<EntryId>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// The content as HTML. The CSV format has the content with line breaks removed.
+    pub content: Option<String>,
+    /// Labels of the tags.
+    pub tags: Vec<String>,
+    pub authors: Option<Authors>,
+    pub mimetype: Option<String>,
+    pub language: Option<String>,
+    pub domain_name: Option<String>,
+    pub preview_picture: Option<String>,
+    pub is_archived: Option<bool>,
+    pub is_starred: Option<bool>,
+    pub reading_time: Option<u32>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Where the export written by `Client::export_entries_to` was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportSource {
+    /// The server exported the entries.
+    Server,
+    /// The entries were fetched and combined by the client.
+    Local {
+        /// Number of entries exported.
+        entries: u64,
+    },
+}
+
+/// Result of `Client::export_entries_to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkExport {
+    pub source: ExportSource,
+    /// Bytes written.
+    pub bytes: u64,
+}
+
+/// Progress of `Client::export_entry_to_with_progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportProgress {
+    /// Bytes written so far.
+    pub bytes: u64,
+    /// Size of the export, if the server sent it.
+    pub total: Option<u64>,
+}
+
+/// Decode an export in one of the text based formats: JSON, CSV or XML.
+pub fn decode(format: Format, body: &str) -> Result<Vec<ExportedEntry>, ExportError> {
+    match format {
+        Format::JSON => decode_json(body),
+        Format::CSV => decode_csv(body),
+        Format::XML => decode_xml(body),
+        _ => Err(ExportError::UnsupportedFormat(format)),
+    }
+}
+
+/// Decode a JSON export: an array of entries, or a single entry.
+pub fn decode_json(body: &str) -> Result<Vec<ExportedEntry>, ExportError> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Records {
+        Many(Vec<Map<String, Value>>),
+        One(Map<String, Value>),
+    }
+
+    let records = match serde_json::from_str(body)? {
+        Records::Many(records) => records,
+        Records::One(record) => vec![record],
+    };
+    Ok(records.iter().map(ExportedEntry::from_fields).collect())
+}
+
+/// Decode a CSV export. The server separates fields with semicolons, but commas are accepted
+/// too. Columns are matched by the names in the header row.
+pub fn decode_csv(body: &str) -> Result<Vec<ExportedEntry>, ExportError> {
+    let body = body.trim_start_matches('\u{feff}');
+    let delimiter = match (body.find(';'), body.find(',')) {
+        (Some(semicolon), Some(comma)) if comma < semicolon => ',',
+        (None, Some(_)) => ',',
+        _ => ';',
+    };
+    let mut rows = parse_csv(body, delimiter)
+        .map_err(ExportError::Csv)?
+        .into_iter();
+    let header: Vec<String> = match rows.next() {
+        Some(header) => header.iter().map(|name| csv_key(name)).collect(),
+        None => return Ok(Vec::new()),
+    };
+    Ok(rows
+        .filter(|row| row.iter().any(|field| !field.is_empty()))
+        .map(|row| {
+            let fields: Map<String, Value> = header
+                .iter()
+                .cloned()
+                .zip(row.into_iter().map(Value::String))
+                .collect();
+            ExportedEntry::from_fields(&fields)
+        })
+        .collect())
+}
+
+/// Decode an XML export: a root element with an `entry` element per entry, which has an element
+/// per field. Fields with a list of values, like `tags`, have an element per value.
+pub fn decode_xml(body: &str) -> Result<Vec<ExportedEntry>, ExportError> {
+    let mut reader = Reader::from_str(body);
+    let mut records = Vec::new();
+    let mut record = Map::new();
+    // Name, text and list items of the current field.
+    let mut field: Option<(String, String, Vec<Value>)> = None;
+    let mut item = String::new();
+    let mut depth = 0;
+
+    loop {
+        match reader.read_event().map_err(ExportError::from)? {
+            Event::Start(start) => {
+                depth += 1;
+                match depth {
+                    2 => record = Map::new(),
+                    3 => {
+                        let name = start.local_name().as_ref().to_owned();
+                        field = Some((name, String::new(), Vec::new()));
+                    }
+                    4 => item.clear(),
+                    _ => {}
+                }
+            }
+            Event::End(_) => {
+                match depth {
+                    2 => records.push(ExportedEntry::from_fields(&record)),
+                    3 => {
+                        if let Some((name, text, items)) = field.take() {
+                            let value = if items.is_empty() {
+                                Value::String(text.trim().to_owned())
+                            } else {
+                                Value::Array(items)
+                            };
+                            record.insert(name, value);
+                        }
+                    }
+                    4 => {
+                        if let Some((_, _, ref mut items)) = field {
+                            items.push(Value::String(item.trim().to_owned()));
+                        }
+                    }
+                    _ => {}
+                }
+                depth -= 1;
+            }
+            Event::Text(text) => push_text(depth, &mut field, &mut item, &text.xml10_content()),
+            Event::CData(data) => push_text(depth, &mut field, &mut item, &data.xml10_content()),
+            Event::GeneralRef(reference) => {
+                let resolved = match reference.resolve_char_ref()? {
+                    Some(c) => c.to_string(),
+                    None => {
+                        let escaped = format!("&{};", reference.xml10_content());
+                        quick_xml::escape::unescape(&escaped)
+                            .map(|unescaped| unescaped.into_owned())
+                            .unwrap_or(escaped)
+                    }
+                };
+                push_text(depth, &mut field, &mut item, &resolved);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+/// Add text to the current field, or to the current item of a list field.
+fn push_text(
+    depth: usize,
+    field: &mut Option<(String, String, Vec<Value>)>,
+    item: &mut String,
+    text: &str,
+) {
+    if depth >= 4 {
+        item.push_str(text);
+    } else if let (3, Some((_, ref mut field_text, _))) = (depth, field) {
+        field_text.push_str(text);
+    }
+}
+
+impl ExportedEntry {
+    /// Build an entry from the fields of any of the formats. Values that can't be interpreted
+    /// are ignored.
+    fn from_fields(fields: &Map<String, Value>) -> Self {
+        let string = |key: &str| match fields.get(key) {
+            Some(Value::String(s)) if !s.is_empty() => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        };
+        let boolean = |key: &str| match fields.get(key) {
+            Some(Value::Bool(b)) => Some(*b),
+            Some(Value::Number(n)) => n.as_i64().map(|n| n != 0),
+            Some(Value::String(s)) => match s.as_str() {
+                "1" | "true" => Some(true),
+                "0" | "false" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        };
+        let date = |key: &str| string(key).and_then(|s| parse_date(&s));
+
+        ExportedEntry {
+            id: string("id").and_then(|id| id.parse().ok()).map(EntryId),
+            title: string("title"),
+            url: string("url"),
+            content: string("content"),
+            tags: fields.get("tags").map(tag_labels).unwrap_or_default(),
+            authors: fields
+                .get("published_by")
+                .and_then(|authors| Authors::deserialize(authors).ok())
+                .filter(|authors| !authors.is_empty()),
+            mimetype: string("mimetype"),
+            language: string("language"),
+            domain_name: string("domain_name"),
+            preview_picture: string("preview_picture"),
+            is_archived: boolean("is_archived"),
+            is_starred: boolean("is_starred"),
+            reading_time: string("reading_time").and_then(|time| time.parse().ok()),
+            created_at: date("created_at"),
+            updated_at: date("updated_at"),
+            published_at: date("published_at"),
+        }
+    }
+}
+
+/// Tags are exported as a list of labels, a list of tag objects, or a comma separated string.
+fn tag_labels(tags: &Value) -> Vec<String> {
+    let labels: Vec<String> = match tags {
+        Value::Array(tags) => tags
+            .iter()
+            .filter_map(|tag| match tag {
+                Value::String(label) => Some(label.clone()),
+                Value::Object(tag) => tag.get("label")?.as_str().map(str::to_owned),
+                _ => None,
+            })
+            .collect(),
+        Value::String(tags) => tags.split(',').map(str::to_owned).collect(),
+        _ => Vec::new(),
+    };
+    labels
+        .into_iter()
+        .map(|label| label.trim().to_owned())
+        .filter(|label| !label.is_empty())
+        .collect()
+}
+
+/// Dates are RFC 3339 in JSON exports, with or without a colon in the offset, and day first
+/// without a time zone in CSV exports.
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .or_else(|_| DateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%z"))
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(date, "%d/%m/%Y %H:%M:%S")
+                .ok()
+                .map(|date| date.and_utc())
+        })
+}
+
+/// The key used in the other formats for a CSV column.
+fn csv_key(name: &str) -> String {
+    match name.trim().to_lowercase().as_str() {
+        "mime type" => "mimetype".to_owned(),
+        "creation date" => "created_at".to_owned(),
+        name => name.replace(' ', "_"),
+    }
+}
+
+/// Quote a CSV field if needed.
+pub(crate) fn csv_field(field: &str, delimiter: char) -> String {
+    if field.contains(&[delimiter, '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// The header of CSV exports, as written by the server.
+pub(crate) const CSV_HEADER: &str = "Title;URL;Content;Tags;MIME Type;Language;Creation date\n";
+
+/// An entry as a row of a CSV export, in the same format as the server.
+pub(crate) fn csv_row(entry: &Entry) -> String {
+    let tags: Vec<&str> = entry.tags.iter().map(|tag| tag.label.as_str()).collect();
+    let content = entry.content.as_deref().unwrap_or_default();
+    let row = [
+        entry.title.clone().unwrap_or_default(),
+        entry.url.clone().unwrap_or_default(),
+        content.replace(&['\r', '\n'][..], ""),
+        tags.join(", "),
+        entry.mimetype.clone().unwrap_or_default(),
+        entry.language.clone().unwrap_or_default(),
+        entry.created_at.format("%d/%m/%Y %H:%M:%S").to_string(),
+    ];
+    let row: Vec<String> = row.iter().map(|field| csv_field(field, ';')).collect();
+    format!("{}\n", row.join(";"))
+}
+
+/// An entry as a record of a JSON export, with the same fields as the server writes. Unlike the
+/// API, the export has no user or sharing details, lists tags by label and always includes the
+/// annotations.
+pub(crate) fn json_record(entry: &Entry) -> Value {
+    let tags: Vec<&str> = entry.tags.iter().map(|tag| tag.label.as_str()).collect();
+    json!({
+        "id": entry.id,
+        "title": entry.title,
+        "url": entry.url,
+        "origin_url": entry.origin_url,
+        "is_archived": u8::from(entry.is_archived),
+        "is_starred": u8::from(entry.is_starred),
+        "content": entry.content,
+        "created_at": entry.created_at,
+        "updated_at": entry.updated_at,
+        "published_at": entry.published_at,
+        "published_by": entry.published_by.as_ref().map(Authors::names),
+        "annotations": entry.annotations.as_deref().unwrap_or_default(),
+        "mimetype": entry.mimetype,
+        "language": entry.language,
+        "reading_time": entry.reading_time,
+        "domain_name": entry.domain_name,
+        "preview_picture": entry.preview_picture,
+        "http_status": entry.http_status,
+        "headers": entry.headers,
+        "tags": tags,
+    })
+}
+
+/// The category export of the web interface matching a filter, with the label of the tag for
+/// the `tag_entries` category. `None` if the filter can't be expressed as a category.
+pub(crate) fn export_category(filter: &EntriesFilter) -> Option<(&'static str, Option<&str>)> {
+    if filter.since != 0 || filter.public.is_some() {
+        return None;
+    }
+    match (filter.archive, filter.starred, filter.tags.as_slice()) {
+        (None, None, []) => Some(("all", None)),
+        (Some(false), None, []) => Some(("unread", None)),
+        (Some(true), None, []) => Some(("archive", None)),
+        (None, Some(true), []) => Some(("starred", None)),
+        (None, None, [tag]) => Some(("tag_entries", Some(tag))),
+        _ => None,
+    }
+}
+
+/// Split CSV into rows of fields. Fields can be quoted, with doubled quotes inside.
+pub(crate) fn parse_csv(body: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_owned());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use crate::types::NewEntry;
+    use crate::ClientError;
+    use async_std::task::block_on;
+    use surf::http::{self, StatusCode};
+
+    #[test]
+    fn test_decode_formats() {
+        let json = r#"[{"id": 7, "title": "Title", "url": "https://example.com/",
+            "tags": [{"id": 1, "label": "rust", "slug": "rust"}], "is_archived": 1,
+            "published_by": ["Ada", null], "created_at": "2017-03-04T10:33:49+0100"}]"#;
+        let entry = &decode_json(json).unwrap()[0];
+        assert_eq!(entry.id, Some(EntryId(7)));
+        assert_eq!(entry.tags, ["rust"]);
+        assert_eq!(entry.is_archived, Some(true));
+        assert_eq!(entry.authors, Some(Authors::new(["Ada"])));
+        assert_eq!(
+            entry.created_at.unwrap().to_rfc3339(),
+            "2017-03-04T09:33:49+00:00"
+        );
+
+        let csv = "\"Title\",\"URL\",\"Content\",\"Tags\"\r\n\
+                   \"Say \"\"hi\"\", twice\",https://example.com/,\"a\nb\",\"one, two\"\r\n";
+        let entry = &decode_csv(csv).unwrap()[0];
+        assert_eq!(entry.title.as_deref(), Some("Say \"hi\", twice"));
+        assert_eq!(entry.content.as_deref(), Some("a\nb"));
+        assert_eq!(entry.tags, ["one", "two"]);
+        assert!(decode_csv("Title\n\"open").is_err());
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <result>
+              <entry>
+                <id>3</id>
+                <title><![CDATA[Fish & <chips>]]></title>
+                <url>https://example.com/?a=1&amp;b=2</url>
+                <tags><entry><![CDATA[food]]></entry><entry>uk</entry></tags>
+                <is_starred>true</is_starred>
+              </entry>
+            </result>"#;
+        let entry = &decode_xml(xml).unwrap()[0];
+        assert_eq!(entry.id, Some(EntryId(3)));
+        assert_eq!(entry.title.as_deref(), Some("Fish & <chips>"));
+        assert_eq!(entry.url.as_deref(), Some("https://example.com/?a=1&b=2"));
+        assert_eq!(entry.tags, ["food", "uk"]);
+        assert_eq!(entry.is_starred, Some(true));
+
+        assert!(matches!(
+            decode(Format::PDF, ""),
+            Err(ExportError::UnsupportedFormat(Format::PDF))
+        ));
+    }
+
+    #[test]
+    fn test_export_entry_records_and_bytes() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let mut new_entry = NewEntry::new_with_url("https://example.com/".to_owned());
+            new_entry.title = Some("Exported".to_owned());
+            new_entry.tags = Some(vec!["a".to_owned(), "b".to_owned()]);
+            let entry = client.create_entry(&new_entry).await.unwrap();
+
+            for format in [Format::JSON, Format::CSV, Format::XML] {
+                let records = client.export_entry_records(entry.id, format).await.unwrap();
+                assert_eq!(records.len(), 1);
+                assert_eq!(records[0].title.as_deref(), Some("Exported"), "{}", format);
+                assert_eq!(records[0].url, entry.url, "{}", format);
+            }
+
+            let pdf = client
+                .export_entry_bytes(entry.id, Format::PDF)
+                .await
+                .unwrap();
+            assert!(pdf.starts_with(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3"));
+
+            let sent = server.requests().len();
+            let err = client
+                .export_entry_records(entry.id, Format::EPUB)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                crate::ClientError::Export(ExportError::UnsupportedFormat(Format::EPUB))
+            ));
+            assert_eq!(server.requests().len(), sent);
+        })
+    }
+
+    #[test]
+    fn test_export_entry_to() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let entry = server.add_entry("https://example.com/", &[]);
+
+            let mut file = Vec::new();
+            let mut progress = Vec::new();
+            let written = client
+                .export_entry_to_with_progress(entry.id, Format::EPUB, &mut file, |p| {
+                    progress.push(p)
+                })
+                .await
+                .unwrap();
+            assert!(file.starts_with(b"PK\x03\x04"));
+            assert_eq!(written, file.len() as u64);
+            assert_eq!(
+                progress.last(),
+                Some(&ExportProgress {
+                    bytes: written,
+                    total: Some(written)
+                })
+            );
+
+            // A misconfigured proxy might answer exports with a login page.
+            let path = format!("/api/entries/{}/export.pdf", entry.id.as_int());
+            server.respond(
+                &path,
+                StatusCode::Ok,
+                Some(http::mime::HTML),
+                b"<html>Log in</html>",
+            );
+            let mut client = server.client();
+            let mut file = Vec::new();
+            let err = client
+                .export_entry_to(entry.id, Format::PDF, &mut file)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                ClientError::Export(ExportError::ContentType {
+                    format: Format::PDF,
+                    content_type: Some(ref content_type),
+                }) if content_type == "text/html"
+            ));
+            assert!(file.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_export_entries_to() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            server.add_entry("https://example.com/1", &["a"]);
+            server.add_entry("https://example.com/2", &["a", "b"]);
+            server.add_entry("https://example.com/3", &["b"]);
+
+            // The mock server has no category export, so the entries are combined locally.
+            let filter = EntriesFilter {
+                tags: vec!["a".to_owned()],
+                per_page: Some(1),
+                ..Default::default()
+            };
+            let mut json = Vec::new();
+            let export = client
+                .export_entries_to(&filter, Format::JSON, &mut json)
+                .await
+                .unwrap();
+            assert_eq!(export.source, ExportSource::Local { entries: 2 });
+            assert_eq!(export.bytes, json.len() as u64);
+            let mut urls: Vec<String> = decode_json(std::str::from_utf8(&json).unwrap())
+                .unwrap()
+                .into_iter()
+                .filter_map(|entry| entry.url)
+                .collect();
+            urls.sort();
+            assert_eq!(urls, ["https://example.com/1", "https://example.com/2"]);
+            let records: Vec<Value> = serde_json::from_slice(&json).unwrap();
+            assert!(records
+                .iter()
+                .any(|record| record["tags"] == json!(["a", "b"])));
+            assert_eq!(records[0]["is_archived"], json!(0));
+            assert!(records[0].get("user_name").is_none());
+
+            let mut csv = Vec::new();
+            client
+                .export_entries_to(&EntriesFilter::default(), Format::CSV, &mut csv)
+                .await
+                .unwrap();
+            let entries = decode_csv(std::str::from_utf8(&csv).unwrap()).unwrap();
+            assert_eq!(entries.len(), 3);
+            assert!(entries.iter().any(|entry| entry.tags == ["a", "b"]));
+
+            let err = client
+                .export_entries_to(&EntriesFilter::default(), Format::PDF, &mut Vec::new())
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                ClientError::Export(ExportError::UnsupportedFormat(Format::PDF))
+            ));
+
+            server.respond(
+                "/export/unread.epub",
+                StatusCode::Ok,
+                Some(http::mime::Mime::from("application/epub+zip")),
+                b"PK\x03\x04",
+            );
+            let filter = EntriesFilter {
+                archive: Some(false),
+                ..Default::default()
+            };
+            let mut epub = Vec::new();
+            let export = client
+                .export_entries_to(&filter, Format::EPUB, &mut epub)
+                .await
+                .unwrap();
+            assert_eq!(export.source, ExportSource::Server);
+            assert_eq!(epub, b"PK\x03\x04");
+        })
+    }
+
+    #[test]
+    fn test_export_tag_category() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            server.add_entry("https://example.com/1", &["Rust Lang"]);
+
+            // The web interface selects the tag by slug, not by label.
+            server.respond(
+                "/export/tag_entries.json?tag=rust-lang",
+                StatusCode::Ok,
+                Some(http::mime::JSON),
+                br#"[{"id": 1}]"#,
+            );
+            let filter = EntriesFilter {
+                tags: vec!["Rust Lang".to_owned()],
+                ..Default::default()
+            };
+            let mut json = Vec::new();
+            let export = client
+                .export_entries_to(&filter, Format::JSON, &mut json)
+                .await
+                .unwrap();
+            assert_eq!(export.source, ExportSource::Server);
+            assert_eq!(json, br#"[{"id": 1}]"#);
+
+            // An unknown tag has no category export, so it isn't requested.
+            let filter = EntriesFilter {
+                tags: vec!["missing".to_owned()],
+                ..Default::default()
+            };
+            let export = client
+                .export_entries_to(&filter, Format::JSON, &mut Vec::new())
+                .await
+                .unwrap();
+            assert_eq!(export.source, ExportSource::Local { entries: 0 });
+            let exports = server
+                .requests()
+                .into_iter()
+                .filter(|request| request.contains("/export/"))
+                .count();
+            assert_eq!(exports, 1);
+        })
+    }
+}
@@ -0,0 +1,428 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Atom and RSS feeds of entries.
+//!
+//! The feeds of wallabag itself need a token per user, and only cover a few fixed lists of
+//! entries. `Client::get_feed` builds a feed of the entries matching any filter instead, which can
+//! then be served as Atom 1.0 or RSS 2.0. Each item has the content of the entry, its tags as
+//! categories, and its publication and update time.
+//!
+//! ```no_run
+//! # async fn run(client: &mut wallabag_api::Client) -> Result<(), Box<dyn std::error::Error>> {
+//! use wallabag_api::types::EntriesFilter;
+//!
+//! let filter = EntriesFilter {
+//!     tags: vec!["reading-list".to_owned()],
+//!     ..Default::default()
+//! };
+//! let feed = client
+//!     .get_feed("Reading list", "https://example.com/reading-list", &filter)
+//!     .await?
+//!     .with_self_link("https://example.com/reading-list.atom");
+//! std::fs::write("reading-list.atom", feed.to_atom())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::errors::ClientResult;
+use crate::types::{Entries, EntriesFilter, Entry};
+use crate::utils::xml::escape;
+use crate::Client;
+
+/// The syndication formats a feed can be written as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+impl FeedFormat {
+    /// The content type to serve a feed of this format with.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "application/atom+xml",
+            FeedFormat::Rss => "application/rss+xml",
+        }
+    }
+}
+
+/// A feed of entries, in the order they were given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed {
+    title: String,
+    link: String,
+    self_link: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    entries: Entries,
+}
+
+impl Feed {
+    /// Create a feed. `link` is the page the feed belongs to. It is also used as the id of the
+    /// feed, and to derive the ids of the items.
+    pub fn new<T: Into<String>, L: Into<String>>(title: T, link: L, entries: Entries) -> Self {
+        Feed {
+            title: title.into(),
+            link: link.into(),
+            self_link: None,
+            description: None,
+            author: None,
+            entries,
+        }
+    }
+
+    /// Set the url the feed itself is served at. Feed readers use it to find the feed again.
+    pub fn with_self_link<T: Into<String>>(mut self, self_link: T) -> Self {
+        self.self_link = Some(self_link.into());
+        self
+    }
+
+    /// Set the description of the feed. The default is the title.
+    pub fn with_description<T: Into<String>>(mut self, description: T) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the author of the feed, which is used for entries without authors. The default is the
+    /// title of the feed.
+    pub fn with_author<T: Into<String>>(mut self, author: T) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    pub fn entries(&self) -> &Entries {
+        &self.entries
+    }
+
+    /// Write the feed in the given format.
+    pub fn render(&self, format: FeedFormat) -> String {
+        match format {
+            FeedFormat::Atom => self.to_atom(),
+            FeedFormat::Rss => self.to_rss(),
+        }
+    }
+
+    /// Write the feed as an Atom 1.0 document.
+    pub fn to_atom(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        element(&mut out, 1, "id", &self.link);
+        element(&mut out, 1, "title", &self.title);
+        if let Some(ref description) = self.description {
+            element(&mut out, 1, "subtitle", description);
+        }
+        element(&mut out, 1, "updated", &atom_date(self.updated()));
+        link(&mut out, 1, "alternate", &self.link, None);
+        if let Some(ref self_link) = self.self_link {
+            link(
+                &mut out,
+                1,
+                "self",
+                self_link,
+                Some(FeedFormat::Atom.content_type()),
+            );
+        }
+        // Atom requires an author for every entry, entries without one inherit this one.
+        out.push_str(&format!(
+            "  <author><name>{}</name></author>\n",
+            escape(self.author.as_ref().unwrap_or(&self.title))
+        ));
+        element(&mut out, 1, "generator", "wallabag-api");
+
+        for entry in &self.entries {
+            out.push_str("  <entry>\n");
+            element(&mut out, 2, "id", &self.item_id(entry));
+            element(&mut out, 2, "title", &entry.display_title());
+            match entry.url {
+                Some(ref url) => link(&mut out, 2, "alternate", url, None),
+                // An entry without content needs an alternate link.
+                None if entry.content.is_none() => {
+                    link(&mut out, 2, "alternate", &self.item_id(entry), None)
+                }
+                None => {}
+            }
+            element(&mut out, 2, "published", &atom_date(published(entry)));
+            element(&mut out, 2, "updated", &atom_date(entry.updated_at));
+            for name in authors(entry) {
+                out.push_str(&format!(
+                    "    <author><name>{}</name></author>\n",
+                    escape(name)
+                ));
+            }
+            for tag in &entry.tags {
+                out.push_str(&format!(
+                    "    <category term=\"{}\" label=\"{}\"/>\n",
+                    escape(&tag.slug),
+                    escape(&tag.label)
+                ));
+            }
+            if let Some(ref content) = entry.content {
+                out.push_str(&format!(
+                    "    <content type=\"html\">{}</content>\n",
+                    escape(content)
+                ));
+            }
+            out.push_str("  </entry>\n");
+        }
+        out.push_str("</feed>\n");
+        out
+    }
+
+    /// Write the feed as an RSS 2.0 document. RSS has no update time for items, so it is written
+    /// as a Dublin Core `dcterms:modified` element.
+    pub fn to_rss(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        out.push_str(
+            "<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" \
+             xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+             xmlns:dcterms=\"http://purl.org/dc/terms/\">\n",
+        );
+        out.push_str("  <channel>\n");
+        element(&mut out, 2, "title", &self.title);
+        element(&mut out, 2, "link", &self.link);
+        element(
+            &mut out,
+            2,
+            "description",
+            self.description.as_ref().unwrap_or(&self.title),
+        );
+        element(&mut out, 2, "lastBuildDate", &self.updated().to_rfc2822());
+        if let Some(ref self_link) = self.self_link {
+            out.push_str(&format!(
+                "    <atom:link rel=\"self\" href=\"{}\" type=\"{}\"/>\n",
+                escape(self_link),
+                FeedFormat::Rss.content_type()
+            ));
+        }
+        element(&mut out, 2, "generator", "wallabag-api");
+
+        for entry in &self.entries {
+            out.push_str("    <item>\n");
+            element(&mut out, 3, "title", &entry.display_title());
+            if let Some(ref url) = entry.url {
+                element(&mut out, 3, "link", url);
+            }
+            out.push_str(&format!(
+                "      <guid isPermaLink=\"false\">{}</guid>\n",
+                escape(&self.item_id(entry))
+            ));
+            element(&mut out, 3, "pubDate", &published(entry).to_rfc2822());
+            element(
+                &mut out,
+                3,
+                "dcterms:modified",
+                &atom_date(entry.updated_at),
+            );
+            // The author element of RSS must be an email address, so use Dublin Core instead.
+            for name in authors(entry) {
+                element(&mut out, 3, "dc:creator", name);
+            }
+            for tag in &entry.tags {
+                element(&mut out, 3, "category", &tag.label);
+            }
+            if let Some(ref content) = entry.content {
+                element(&mut out, 3, "description", content);
+            }
+            out.push_str("    </item>\n");
+        }
+        out.push_str("  </channel>\n</rss>\n");
+        out
+    }
+
+    /// The time the feed was last changed: the last update of any of its entries.
+    fn updated(&self) -> DateTime<Utc> {
+        self.entries
+            .iter()
+            .map(|entry| entry.updated_at)
+            .max()
+            .unwrap_or_else(Utc::now)
+    }
+
+    /// A stable id for an entry, which is unique as long as feeds have different links.
+    fn item_id(&self, entry: &Entry) -> String {
+        format!("{}#entry-{}", self.link, entry.id)
+    }
+}
+
+impl Client {
+    /// Get a feed of the entries matching the filter. `link` is the page the feed belongs to,
+    /// see `Feed::new`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn get_feed(
+        &mut self,
+        title: &str,
+        link: &str,
+        filter: &EntriesFilter,
+    ) -> ClientResult<Feed> {
+        let entries = self.get_entries_with_filter(filter).await?;
+        Ok(Feed::new(title, link, entries))
+    }
+}
+
+fn element(out: &mut String, depth: usize, name: &str, text: &str) {
+    out.push_str(&format!(
+        "{}<{1}>{2}</{1}>\n",
+        "  ".repeat(depth),
+        name,
+        escape(text)
+    ));
+}
+
+fn link(out: &mut String, depth: usize, rel: &str, href: &str, content_type: Option<&str>) {
+    out.push_str(&format!(
+        "{}<link rel=\"{}\" href=\"{}\"",
+        "  ".repeat(depth),
+        rel,
+        escape(href)
+    ));
+    if let Some(content_type) = content_type {
+        out.push_str(&format!(" type=\"{}\"", content_type));
+    }
+    out.push_str("/>\n");
+}
+
+fn atom_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// The time the entry was published, or saved if that is unknown.
+fn published(entry: &Entry) -> DateTime<Utc> {
+    entry.published_at.unwrap_or(entry.created_at)
+}
+
+fn authors(entry: &Entry) -> impl Iterator<Item = &String> {
+    entry
+        .published_by
+        .iter()
+        .flat_map(|authors| authors.names())
+        .filter(|name| !name.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use crate::types::NewEntry;
+    use async_std::task::block_on;
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    /// Check that the document is well-formed, and collect the text of all elements with the
+    /// given name.
+    fn texts(document: &str, name: &str) -> Vec<String> {
+        let mut reader = Reader::from_str(document);
+        let mut current: Option<String> = None;
+        let mut texts = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(start) if start.name().as_ref() == name => {
+                    current = Some(String::new())
+                }
+                Event::Text(text) => {
+                    if let Some(ref mut current) = current {
+                        current.push_str(&text.xml10_content());
+                    }
+                }
+                Event::GeneralRef(reference) => {
+                    if let Some(ref mut current) = current {
+                        match reference.resolve_char_ref().unwrap() {
+                            Some(c) => current.push(c),
+                            None => current.push_str(
+                                &quick_xml::escape::unescape(&format!(
+                                    "&{};",
+                                    reference.xml10_content()
+                                ))
+                                .unwrap(),
+                            ),
+                        }
+                    }
+                }
+                Event::End(_) => texts.extend(current.take()),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        texts
+    }
+
+    #[test]
+    fn test_get_feed() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let mut new_entry = NewEntry::new_with_url("https://example.com/feeds".to_owned());
+            new_entry.title = Some("Feeds & <things>".to_owned());
+            new_entry.content = Some("<p>Read <em>this</em>.</p>".to_owned());
+            new_entry.authors = Some("Ada Lovelace, Charles Babbage".into());
+            new_entry.published_at = Some("2024-03-01T12:00:00Z".parse().unwrap());
+            new_entry.tags = Some(vec!["reading-list".to_owned(), "web".to_owned()]);
+            let entry = client.create_entry(&new_entry).await.unwrap();
+            server.add_entry("https://example.com/other", &[]);
+
+            let filter = EntriesFilter {
+                tags: vec!["reading-list".to_owned()],
+                ..Default::default()
+            };
+            let feed = client
+                .get_feed("Reading list", "https://example.com/list", &filter)
+                .await
+                .unwrap()
+                .with_self_link("https://example.com/list.xml");
+            assert_eq!(feed.entries().len(), 1);
+            let id = format!("https://example.com/list#entry-{}", entry.id);
+
+            let atom = feed.to_atom();
+            assert_eq!(texts(&atom, "id"), vec!["https://example.com/list", &id]);
+            assert_eq!(
+                texts(&atom, "title"),
+                vec!["Reading list", "Feeds & <things>"]
+            );
+            assert_eq!(texts(&atom, "content"), vec!["<p>Read <em>this</em>.</p>"]);
+            assert_eq!(
+                texts(&atom, "name"),
+                vec!["Reading list", "Ada Lovelace", "Charles Babbage"]
+            );
+            assert_eq!(texts(&atom, "published"), vec!["2024-03-01T12:00:00Z"]);
+            assert!(atom.contains("<category term=\"web\" label=\"web\"/>"));
+            assert!(atom.contains(
+                "<link rel=\"self\" href=\"https://example.com/list.xml\" \
+                 type=\"application/atom+xml\"/>"
+            ));
+
+            let rss = feed.render(FeedFormat::Rss);
+            assert_eq!(texts(&rss, "guid"), vec![id]);
+            assert_eq!(texts(&rss, "category"), vec!["reading-list", "web"]);
+            assert_eq!(
+                texts(&rss, "pubDate"),
+                vec!["Fri, 1 Mar 2024 12:00:00 +0000"]
+            );
+            assert_eq!(
+                texts(&rss, "description"),
+                vec!["Reading list", "<p>Read <em>this</em>.</p>"]
+            );
+            assert_eq!(
+                texts(&rss, "dcterms:modified"),
+                vec![atom_date(feed.entries()[0].updated_at)]
+            );
+        })
+    }
+
+    #[test]
+    fn test_atom_entry_without_url_or_content() {
+        let server = MockServer::new();
+        let entry = Entry {
+            url: None,
+            content: None,
+            ..server.add_entry("https://example.com/a", &[])
+        };
+        let id = format!("https://example.com/list#entry-{}", entry.id);
+        let feed = Feed::new("List", "https://example.com/list", vec![entry]);
+
+        let atom = feed.to_atom();
+        assert_eq!(texts(&atom, "content"), Vec::<String>::new());
+        assert!(atom.contains(&format!("<link rel=\"alternate\" href=\"{}\"/>", id)));
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Exporting annotations together with the entries they belong to.
+//!
+//! `Client::get_highlights` collects the annotations of all entries matching a filter. They can
+//! then be written as Markdown files with front matter, one per entry, which can be dropped into
+//! a note taking tool like Obsidian. `to_readwise_csv` produces a CSV file that can be imported
+//! into Readwise, and `to_json` a JSON document for other tools.
+//!
+//! ```no_run
+//! # async fn run(client: &mut wallabag_api::Client) -> Result<(), Box<dyn std::error::Error>> {
+//! use wallabag_api::highlights::{to_readwise_csv, write_markdown_files};
+//! use wallabag_api::types::EntriesFilter;
+//!
+//! let highlights = client.get_highlights(&EntriesFilter::default()).await?;
+//! write_markdown_files("notes".as_ref(), &highlights)?;
+//! std::fs::write("readwise.csv", to_readwise_csv(&highlights))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::errors::ClientResult;
+use crate::export::csv_field;
+use crate::types::{Annotation, AnnotationId, Annotations, EntriesFilter, Entry, EntryId};
+use crate::Client;
+
+/// Maximum number of annotation requests sent at a time by `Client::get_highlights`.
+const CONCURRENCY: usize = 4;
+
+/// An entry and its annotations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryHighlights {
+    pub entry: Entry,
+    pub annotations: Annotations,
+}
+
+impl Client {
+    /// Get the annotations of all entries matching the filter. Entries without annotations are
+    /// left out.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn get_highlights(
+        &mut self,
+        filter: &EntriesFilter,
+    ) -> ClientResult<Vec<EntryHighlights>> {
+        let entries = self.get_entries_with_filter(filter).await?;
+        let mut annotations = self
+            .get_annotations_for_entries(&entries, CONCURRENCY)
+            .await?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| {
+                let annotations = annotations.remove(&entry.id)?;
+                if annotations.is_empty() {
+                    None
+                } else {
+                    Some(EntryHighlights { entry, annotations })
+                }
+            })
+            .collect())
+    }
+}
+
+/// Format the annotations of an entry as Markdown, with the details of the entry in YAML front
+/// matter. Each quote is a block quote, followed by the note of the annotation.
+pub fn to_markdown(highlights: &EntryHighlights) -> String {
+    let entry = &highlights.entry;
+    let mut out = String::from("---\n");
+    front_matter(&mut out, "title", entry.title.as_ref());
+    front_matter(&mut out, "url", entry.url.as_ref());
+    if let Some(ref authors) = entry.published_by {
+        front_matter_list(&mut out, "authors", authors.names());
+    }
+    let tags: Vec<&str> = entry.tags.iter().map(|tag| tag.label.as_str()).collect();
+    front_matter_list(&mut out, "tags", &tags);
+    front_matter(
+        &mut out,
+        "published",
+        entry.published_at.map(rfc3339).as_ref(),
+    );
+    front_matter(&mut out, "created", Some(&rfc3339(entry.created_at)));
+    front_matter(&mut out, "updated", Some(&rfc3339(entry.updated_at)));
+    out.push_str(&format!("wallabag_id: {}\n---\n", entry.id));
+
+    out.push_str(&format!("\n# {}\n", entry.display_title()));
+    for annotation in &highlights.annotations {
+        out.push('\n');
+        for line in annotation.quote.as_deref().unwrap_or_default().lines() {
+            if line.trim().is_empty() {
+                out.push_str(">\n");
+            } else {
+                out.push_str(&format!("> {}\n", line));
+            }
+        }
+        if !annotation.text.trim().is_empty() {
+            out.push_str(&format!("\n{}\n", annotation.text.trim()));
+        }
+    }
+    out
+}
+
+/// A file name for the Markdown export of an entry: the id followed by the title, with
+/// everything except letters, digits and dashes removed.
+pub fn markdown_file_name(entry: &Entry) -> String {
+    let mut slug = String::new();
+    for c in entry.display_title().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= 60 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        format!("{}.md", entry.id)
+    } else {
+        format!("{}-{}.md", entry.id, slug)
+    }
+}
+
+/// Write the Markdown export of each entry to a separate file in `dir`, which is created if
+/// needed. Existing files are overwritten. Returns the paths of the files.
+pub fn write_markdown_files(
+    dir: &Path,
+    highlights: &[EntryHighlights],
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    highlights
+        .iter()
+        .map(|entry_highlights| {
+            let path = dir.join(markdown_file_name(&entry_highlights.entry));
+            fs::write(&path, to_markdown(entry_highlights))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Format all annotations as CSV in the format imported by Readwise, with one row per
+/// annotation.
+pub fn to_readwise_csv(highlights: &[EntryHighlights]) -> String {
+    let mut out = String::from("Highlight,Title,Author,URL,Note,Location,Date\n");
+    for entry_highlights in highlights {
+        let entry = &entry_highlights.entry;
+        let authors = entry
+            .published_by
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        for (index, annotation) in entry_highlights.annotations.iter().enumerate() {
+            let row = [
+                annotation.quote.clone().unwrap_or_default(),
+                entry.display_title(),
+                authors.clone(),
+                entry.url.clone().unwrap_or_default(),
+                annotation.text.clone(),
+                (index + 1).to_string(),
+                annotation
+                    .created_at
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field, ',')).collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// An entry in the JSON export.
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    id: EntryId,
+    title: Option<&'a str>,
+    url: Option<&'a str>,
+    authors: &'a [String],
+    tags: Vec<&'a str>,
+    published_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    annotations: Vec<JsonAnnotation<'a>>,
+}
+
+/// An annotation in the JSON export.
+#[derive(Serialize)]
+struct JsonAnnotation<'a> {
+    id: AnnotationId,
+    quote: Option<&'a str>,
+    note: &'a str,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl<'a> From<&'a Annotation> for JsonAnnotation<'a> {
+    fn from(annotation: &'a Annotation) -> Self {
+        JsonAnnotation {
+            id: annotation.id,
+            quote: annotation.quote.as_deref(),
+            note: &annotation.text,
+            created_at: annotation.created_at,
+            updated_at: annotation.updated_at,
+        }
+    }
+}
+
+/// Format all entries and their annotations as a JSON array. Unlike the API types, this only
+/// includes the fields that are useful outside of wallabag, so the content of entries is left
+/// out.
+pub fn to_json(highlights: &[EntryHighlights]) -> serde_json::Result<String> {
+    let entries: Vec<JsonEntry> = highlights
+        .iter()
+        .map(|EntryHighlights { entry, annotations }| JsonEntry {
+            id: entry.id,
+            title: entry.title.as_deref(),
+            url: entry.url.as_deref(),
+            authors: entry
+                .published_by
+                .as_ref()
+                .map_or(&[], |authors| authors.names()),
+            tags: entry.tags.iter().map(|tag| tag.label.as_str()).collect(),
+            published_at: entry.published_at,
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+            annotations: annotations.iter().map(JsonAnnotation::from).collect(),
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries)
+}
+
+fn rfc3339(date: DateTime<Utc>) -> String {
+    date.to_rfc3339()
+}
+
+/// Add a string to the front matter. JSON strings are valid YAML, so they take care of quoting.
+fn front_matter(out: &mut String, key: &str, value: Option<&String>) {
+    if let Some(value) = value {
+        out.push_str(&format!(
+            "{}: {}\n",
+            key,
+            serde_json::Value::from(value.as_str())
+        ));
+    }
+}
+
+fn front_matter_list<T: AsRef<str>>(out: &mut String, key: &str, values: &[T]) {
+    let values: Vec<&str> = values.iter().map(AsRef::as_ref).collect();
+    out.push_str(&format!("{}: {}\n", key, serde_json::Value::from(values)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use crate::types::{NewAnnotation, NewEntry, Range};
+    use async_std::task::block_on;
+
+    fn new_annotation(quote: &str, text: &str) -> NewAnnotation {
+        NewAnnotation {
+            quote: quote.to_owned(),
+            ranges: vec![Range {
+                start: Some("/p[1]".to_owned()),
+                end: Some("/p[1]".to_owned()),
+                start_offset: 0,
+                end_offset: quote.len() as u32,
+            }],
+            text: text.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_get_annotations_for_entries() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let mut entries = Vec::new();
+            for i in 0..5 {
+                let entry = server.add_entry(&format!("https://example.com/{}", i), &[]);
+                client
+                    .create_annotation(entry.id, &new_annotation("Content", &i.to_string()))
+                    .await
+                    .unwrap();
+                entries.push(client.get_entry(entry.id).await.unwrap());
+            }
+            // As if the listing didn't include the annotations of some entries.
+            entries[1].annotations = None;
+            entries[3].annotations = None;
+            let sent = server.requests().len();
+
+            let annotations = client
+                .get_annotations_for_entries(&entries, 2)
+                .await
+                .unwrap();
+            assert_eq!(annotations.len(), 5);
+            for (i, entry) in entries.iter().enumerate() {
+                assert_eq!(annotations[&entry.id][0].text, i.to_string());
+            }
+            let requests = &server.requests()[sent..];
+            assert_eq!(requests.len(), 2);
+            assert!(requests.iter().all(|r| r.contains("/api/annotations/")));
+        })
+    }
+
+    #[test]
+    fn test_get_annotations_for_entries_refreshes_once() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let mut entries = Vec::new();
+            for i in 0..4 {
+                let mut entry = server.add_entry(&format!("https://example.com/{}", i), &[]);
+                entry.annotations = None;
+                entries.push(entry);
+            }
+            client.get_tags().await.unwrap();
+            server.expire_tokens();
+            let sent = server.requests().len();
+
+            let annotations = client
+                .get_annotations_for_entries(&entries, 3)
+                .await
+                .unwrap();
+            assert_eq!(annotations.len(), 4);
+            let requests = &server.requests()[sent..];
+            assert_eq!(
+                requests
+                    .iter()
+                    .filter(|r| r.as_str() == "POST /oauth/v2/token")
+                    .count(),
+                1
+            );
+            assert_eq!(requests.len(), 6);
+
+            // The refreshed token is kept.
+            client.get_tags().await.unwrap();
+            assert_eq!(server.requests().len(), sent + 7);
+        })
+    }
+
+    #[test]
+    fn test_export_highlights() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            server.add_entry("https://example.com/empty", &[]);
+            let mut new_entry = NewEntry::new_with_url("https://example.com/post".to_owned());
+            new_entry.title = Some("A \"quoted\" title: part 2".to_owned());
+            new_entry.tags = Some(vec!["rust".to_owned()]);
+            new_entry.authors = Some("Ada Lovelace".into());
+            let entry = client.create_entry(&new_entry).await.unwrap();
+            client
+                .create_annotation(entry.id, &new_annotation("First line\n\nSecond", "A note"))
+                .await
+                .unwrap();
+            client
+                .create_annotation(entry.id, &new_annotation("Plain, simple", ""))
+                .await
+                .unwrap();
+
+            let highlights = client
+                .get_highlights(&EntriesFilter::default())
+                .await
+                .unwrap();
+            assert_eq!(highlights.len(), 1);
+
+            let markdown = to_markdown(&highlights[0]);
+            assert!(markdown.starts_with(
+                "---\ntitle: \"A \\\"quoted\\\" title: part 2\"\n\
+                 url: \"https://example.com/post\"\n\
+                 authors: [\"Ada Lovelace\"]\n\
+                 tags: [\"rust\"]\n"
+            ));
+            assert!(markdown.ends_with(&format!(
+                "wallabag_id: {}\n---\n\n# A \"quoted\" title: part 2\n\n\
+                 > First line\n>\n> Second\n\nA note\n\n> Plain, simple\n",
+                entry.id
+            )));
+            assert_eq!(
+                markdown_file_name(&entry),
+                format!("{}-a-quoted-title-part-2.md", entry.id)
+            );
+
+            let csv = to_readwise_csv(&highlights);
+            let lines: Vec<&str> = csv.lines().collect();
+            assert_eq!(lines[0], "Highlight,Title,Author,URL,Note,Location,Date");
+            assert_eq!(lines[1], "\"First line");
+            assert!(lines[3].starts_with(
+                "Second\",\"A \"\"quoted\"\" title: part 2\",Ada Lovelace,\
+                 https://example.com/post,A note,1,"
+            ));
+            assert!(lines[4].starts_with("\"Plain, simple\","));
+
+            let json: serde_json::Value =
+                serde_json::from_str(&to_json(&highlights).unwrap()).unwrap();
+            assert_eq!(json[0]["tags"], serde_json::json!(["rust"]));
+            assert_eq!(json[0]["annotations"][1]["quote"], "Plain, simple");
+            assert_eq!(json[0]["annotations"][0]["note"], "A note");
+        })
+    }
+}
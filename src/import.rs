@@ -0,0 +1,828 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Importing entries from other read-later and bookmarking services.
+//!
+//! The parsers turn an export of Pocket (HTML or CSV), Instapaper (CSV), Pinboard (JSON) or a
+//! browser (Netscape bookmark HTML) into `ImportItem`s, keeping tags, the archived and starred
+//! state, and the time the url was saved. `Client::import_entries` then creates an entry for
+//! each url that doesn't have one yet, oldest first, and records what happened in an
+//! `ImportReport`.
+//!
+//! The report can be saved and passed in again, to resume an import that was interrupted, eg.
+//! by a network error. Urls that are already in the report are not sent again.
+//!
+//! ```no_run
+//! # async fn run(client: &mut wallabag_api::Client) -> Result<(), Box<dyn std::error::Error>> {
+//! use wallabag_api::import::{parse, ImportFormat, ImportReport};
+//!
+//! let items = parse(ImportFormat::PocketHtml, &std::fs::read_to_string("ril_export.html")?)?;
+//! let mut report: ImportReport = match std::fs::read("import.json") {
+//!     Ok(saved) => serde_json::from_slice(&saved)?,
+//!     Err(_) => ImportReport::default(),
+//! };
+//! let result = client.import_entries(&items, &mut report).await;
+//! std::fs::write("import.json", serde_json::to_vec(&report)?)?;
+//! result?;
+//! println!("Imported {} entries", report.imported.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, TimeZone, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use surf::http::StatusCode;
+
+use crate::errors::{ClientResult, ImportError};
+use crate::export::parse_csv;
+use crate::types::{EntryId, NewEntry};
+use crate::{Client, ClientError};
+
+/// Number of urls checked for existing entries per request.
+const EXISTS_BATCH: usize = 50;
+
+/// The formats of other services that can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportFormat {
+    /// The HTML export of Pocket, with a list of unread and a list of archived links.
+    PocketHtml,
+    /// The CSV export of Pocket, with `title`, `url`, `time_added`, `tags` and `status` columns.
+    PocketCsv,
+    /// The CSV export of Instapaper, with `URL`, `Title`, `Folder`, `Timestamp` and optionally
+    /// `Tags` columns.
+    InstapaperCsv,
+    /// The JSON export of Pinboard.
+    PinboardJson,
+    /// The bookmark files of browsers and many other services.
+    NetscapeHtml,
+}
+
+/// An entry to import, with the time it was saved in the other service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportItem {
+    pub entry: NewEntry,
+    /// The time the url was saved. The API can't set the creation time of entries, so this only
+    /// decides the order in which items are imported.
+    pub added_at: Option<DateTime<Utc>>,
+}
+
+/// What happened to each url of an import. Can be serialized, to resume the import later.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Urls that were imported, with the id of the new entry.
+    pub imported: BTreeMap<String, EntryId>,
+    /// Urls that already had an entry, with its id.
+    pub duplicates: BTreeMap<String, EntryId>,
+    /// Urls that the server refused or that are invalid, with the reason.
+    pub failed: BTreeMap<String, String>,
+}
+
+impl ImportReport {
+    /// Whether the url was handled already. Failed urls are not tried again; remove them from
+    /// `failed` to retry them.
+    pub fn contains(&self, url: &str) -> bool {
+        self.imported.contains_key(url)
+            || self.duplicates.contains_key(url)
+            || self.failed.contains_key(url)
+    }
+
+    /// The number of urls that were handled.
+    pub fn len(&self) -> usize {
+        self.imported.len() + self.duplicates.len() + self.failed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Client {
+    /// Create entries for the items whose url isn't saved yet. Items that are in the report
+    /// already are skipped.
+    ///
+    /// Items are sent oldest first by `added_at`, so the newest item is also the newest entry.
+    /// Items without a time are sent last, in the order they are given.
+    ///
+    /// Items the server refuses, or that don't pass validation, are recorded as failed and the
+    /// import continues. Other errors, like network or authentication errors, stop the import.
+    /// The report then holds everything done so far, so the import can be resumed by calling
+    /// this again with the same report.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn import_entries(
+        &mut self,
+        items: &[ImportItem],
+        report: &mut ImportReport,
+    ) -> ClientResult<()> {
+        let mut seen = HashSet::new();
+        let mut pending: Vec<&ImportItem> = items
+            .iter()
+            .filter(|item| !report.contains(&item.entry.url))
+            .filter(|item| seen.insert(item.entry.url.as_str()))
+            .collect();
+        pending.sort_by_key(|item| (item.added_at.is_none(), item.added_at));
+
+        for batch in pending.chunks(EXISTS_BATCH) {
+            let urls: Vec<&str> = batch.iter().map(|item| item.entry.url.as_str()).collect();
+            let existing = self.check_urls_exist(urls).await?;
+            for item in batch {
+                let url = &item.entry.url;
+                if let Some(Some(id)) = existing.get(url) {
+                    debug!("Skipping {}, it is saved as entry {}", url, id);
+                    report.duplicates.insert(url.clone(), *id);
+                    continue;
+                }
+                match self.create_entry(&item.entry).await {
+                    Ok(entry) => {
+                        report.imported.insert(url.clone(), entry.id);
+                    }
+                    Err(err) if is_item_error(&err) => {
+                        debug!("Importing {} failed: {}", url, err);
+                        report.failed.insert(url.clone(), item_error(&err));
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether the error is caused by the item itself, instead of the connection or account.
+fn is_item_error(err: &ClientError) -> bool {
+    match err {
+        ClientError::Validation(_) => true,
+        _ => err.status().is_some_and(|status| {
+            status.is_client_error()
+                && !err.is_auth()
+                && !matches!(
+                    status,
+                    StatusCode::RequestTimeout | StatusCode::TooManyRequests
+                )
+        }),
+    }
+}
+
+/// A description of the error, including the reason the server gave.
+fn item_error(err: &ClientError) -> String {
+    match err {
+        ClientError::Validation(validation) => format!("{}: {}", err, validation),
+        _ => err.to_string(),
+    }
+}
+
+/// Parse an export in the given format.
+pub fn parse(format: ImportFormat, body: &str) -> Result<Vec<ImportItem>, ImportError> {
+    match format {
+        ImportFormat::PocketHtml => Ok(parse_pocket_html(body)),
+        ImportFormat::PocketCsv => parse_pocket_csv(body),
+        ImportFormat::InstapaperCsv => parse_instapaper_csv(body),
+        ImportFormat::PinboardJson => parse_pinboard_json(body),
+        ImportFormat::NetscapeHtml => Ok(parse_netscape_html(body)),
+    }
+}
+
+/// Parse the HTML export of Pocket. Links below the "Read Archive" heading are archived.
+pub fn parse_pocket_html(body: &str) -> Vec<ImportItem> {
+    parse_links(body)
+        .into_iter()
+        .filter_map(|link| {
+            let archived = link
+                .heading
+                .as_deref()
+                .is_some_and(|heading| heading.to_lowercase().contains("archive"));
+            let mut item = link.into_item(',')?;
+            item.entry.archive = Some(archived);
+            Some(item)
+        })
+        .collect()
+}
+
+/// Parse a bookmark file. Links with a `TOREAD` attribute are unread, others keep the default of
+/// the server.
+pub fn parse_netscape_html(body: &str) -> Vec<ImportItem> {
+    parse_links(body)
+        .into_iter()
+        .filter_map(|link| {
+            let archive = link.attr("toread").map(|toread| toread != "1");
+            let mut item = link.into_item(',')?;
+            item.entry.archive = archive;
+            Some(item)
+        })
+        .collect()
+}
+
+/// Parse the CSV export of Pocket. Tags are separated by `|`.
+pub fn parse_pocket_csv(body: &str) -> Result<Vec<ImportItem>, ImportError> {
+    let table = CsvTable::parse(body)?;
+    let url = table.column("url")?;
+    let title = table.column("title")?;
+    let time_added = table.optional_column("time_added");
+    let tags = table.optional_column("tags");
+    let status = table.optional_column("status");
+    Ok(table
+        .rows()
+        .filter_map(|row| {
+            let mut item = new_item(row.get(url)?, row.get(title))?;
+            item.entry.tags = row.get(tags).map(|tags| split_tags(tags, '|'));
+            item.entry.archive = row.get(status).map(|status| status == "archive");
+            item.added_at = row.get(time_added).and_then(timestamp);
+            Some(item)
+        })
+        .collect())
+}
+
+/// Parse the CSV export of Instapaper. The `Archive` and `Starred` folders set the state of the
+/// entry, other folders besides `Unread` become tags.
+pub fn parse_instapaper_csv(body: &str) -> Result<Vec<ImportItem>, ImportError> {
+    let table = CsvTable::parse(body)?;
+    let url = table.column("url")?;
+    let title = table.optional_column("title");
+    let folder = table.optional_column("folder");
+    let timestamp = table.optional_column("timestamp");
+    let tags = table.optional_column("tags");
+    Ok(table
+        .rows()
+        .filter_map(|row| {
+            let mut item = new_item(row.get(url)?, row.get(title))?;
+            // Newer exports have a column with a JSON list of tags.
+            let mut labels: Vec<String> = row
+                .get(tags)
+                .and_then(|tags| serde_json::from_str(tags).ok())
+                .unwrap_or_default();
+            match row.get(folder) {
+                Some("Archive") => item.entry.archive = Some(true),
+                Some("Starred") => item.entry.starred = Some(true),
+                Some("Unread") | None => {}
+                Some(folder) => labels.push(folder.to_owned()),
+            }
+            labels.retain(|label| !label.trim().is_empty());
+            if !labels.is_empty() {
+                item.entry.tags = Some(labels);
+            }
+            item.added_at = row.get(timestamp).and_then(timestamp_seconds);
+            Some(item)
+        })
+        .collect())
+}
+
+/// Parse the JSON export of Pinboard. Bookmarks that aren't marked to read later are archived,
+/// like the importer of wallabag does.
+pub fn parse_pinboard_json(body: &str) -> Result<Vec<ImportItem>, ImportError> {
+    #[derive(Deserialize)]
+    struct Bookmark {
+        href: String,
+        #[serde(default)]
+        description: String,
+        #[serde(default)]
+        time: Option<String>,
+        #[serde(default)]
+        toread: String,
+        #[serde(default)]
+        tags: String,
+    }
+
+    let bookmarks: Vec<Bookmark> = serde_json::from_str(body)?;
+    Ok(bookmarks
+        .into_iter()
+        .filter_map(|bookmark| {
+            let mut item = new_item(&bookmark.href, Some(&bookmark.description))?;
+            let tags = split_tags(&bookmark.tags, ' ');
+            if !tags.is_empty() {
+                item.entry.tags = Some(tags);
+            }
+            item.entry.archive = Some(bookmark.toread != "yes");
+            item.added_at = bookmark
+                .time
+                .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())
+                .map(|time| time.with_timezone(&Utc));
+            Some(item)
+        })
+        .collect())
+}
+
+/// An item for a url and title, or `None` if it isn't a web url. Bookmark files also contain
+/// eg. `javascript:` and `place:` links.
+fn new_item(url: &str, title: Option<&str>) -> Option<ImportItem> {
+    let url = url.trim();
+    let scheme = url
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if !url.contains("://") || !matches!(scheme.as_str(), "http" | "https") {
+        return None;
+    }
+    let mut entry = NewEntry::new_with_url(url.to_owned());
+    entry.title = title
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_owned);
+    Some(ImportItem {
+        entry,
+        added_at: None,
+    })
+}
+
+fn split_tags(tags: &str, separator: char) -> Vec<String> {
+    tags.split(separator)
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// A Unix timestamp in seconds. Some browsers write milliseconds or microseconds instead, which
+/// are told apart by magnitude: as seconds they would be after the year 5000. Larger values are
+/// rejected.
+fn timestamp(time: &str) -> Option<DateTime<Utc>> {
+    let time: i64 = time.trim().parse().ok()?;
+    match time {
+        i64::MIN..=99_999_999_999 => Utc.timestamp_opt(time, 0).single(),
+        100_000_000_000..=99_999_999_999_999 => Utc.timestamp_millis_opt(time).single(),
+        100_000_000_000_000..=99_999_999_999_999_999 => Utc.timestamp_micros(time).single(),
+        _ => None,
+    }
+}
+
+/// A Unix timestamp in seconds only, for formats that never use anything else.
+fn timestamp_seconds(time: &str) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(time.trim().parse().ok()?, 0).single()
+}
+
+/// A CSV file with a header row.
+struct CsvTable {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    fn parse(body: &str) -> Result<Self, ImportError> {
+        let body = body.trim_start_matches('\u{feff}');
+        let mut rows = parse_csv(body, ',').map_err(ImportError::Csv)?;
+        if rows.is_empty() {
+            return Ok(CsvTable {
+                header: Vec::new(),
+                rows,
+            });
+        }
+        let header = rows
+            .remove(0)
+            .iter()
+            .map(|name| name.trim().to_lowercase())
+            .collect();
+        Ok(CsvTable { header, rows })
+    }
+
+    fn optional_column(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|column| column == name)
+    }
+
+    fn column(&self, name: &'static str) -> Result<Option<usize>, ImportError> {
+        match self.optional_column(name) {
+            Some(index) => Ok(Some(index)),
+            None if self.header.is_empty() => Ok(None),
+            None => Err(ImportError::MissingColumn(name)),
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = CsvRow<'_>> {
+        self.rows.iter().map(|fields| CsvRow { fields })
+    }
+}
+
+struct CsvRow<'a> {
+    fields: &'a [String],
+}
+
+impl<'a> CsvRow<'a> {
+    /// The field in a column, if the column and field exist and aren't empty.
+    fn get(&self, column: Option<usize>) -> Option<&'a str> {
+        self.fields
+            .get(column?)
+            .map(String::as_str)
+            .filter(|field| !field.trim().is_empty())
+    }
+}
+
+/// A link in an HTML export, with the heading above it.
+struct Link {
+    attributes: Vec<(String, String)>,
+    text: String,
+    heading: Option<String>,
+}
+
+impl Link {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn into_item(self, tag_separator: char) -> Option<ImportItem> {
+        let mut item = new_item(self.attr("href")?, Some(&self.text))?;
+        let tags = split_tags(self.attr("tags").unwrap_or_default(), tag_separator);
+        if !tags.is_empty() {
+            item.entry.tags = Some(tags);
+        }
+        item.added_at = self
+            .attr("time_added")
+            .or_else(|| self.attr("add_date"))
+            .and_then(timestamp);
+        Some(item)
+    }
+}
+
+/// Find the links in an HTML export. The exports of Pocket and browsers aren't well-formed, so
+/// this only looks for `a` elements and headings, and ignores everything else.
+fn parse_links(body: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut heading = None;
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        let (attributes, after) = parse_attributes(&rest[name_end..]);
+        rest = after;
+        if name != "a" && !is_heading(&name) {
+            continue;
+        }
+        let end = text_end(rest, &name);
+        let text = html_text(&rest[..end]);
+        rest = &rest[end..];
+        if is_heading(&name) {
+            heading = Some(text);
+        } else if attributes.iter().any(|(name, _)| name == "href") {
+            links.push(Link {
+                attributes,
+                text,
+                heading: heading.clone(),
+            });
+        }
+    }
+    links
+}
+
+/// Parse the attributes of a tag, up to the closing `>`. Returns them with lowercase names, and
+/// the rest of the document.
+fn parse_attributes(tag: &str) -> (Vec<(String, String)>, &str) {
+    let mut attributes = Vec::new();
+    let mut rest = tag;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            return (attributes, rest);
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return (attributes, after);
+        }
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, after) = match after.chars().next() {
+                    Some(quote @ '"') | Some(quote @ '\'') => {
+                        let value = &after[1..];
+                        match value.find(quote) {
+                            Some(end) => (&value[..end], &value[end + 1..]),
+                            None => (value, ""),
+                        }
+                    }
+                    _ => {
+                        let end = after
+                            .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                            .unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = after;
+                decode_entities(value)
+            }
+            None => String::new(),
+        };
+        attributes.push((name, value));
+    }
+}
+
+fn is_heading(name: &str) -> bool {
+    name.len() == 2 && name.starts_with('h') && name[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// The end of the text of an element: its end tag, or the next tag that starts or ends a link,
+/// list item, list or heading. This keeps an unclosed element from taking the links after it.
+fn text_end(html: &str, name: &str) -> usize {
+    let mut offset = 0;
+    while let Some(start) = html[offset..].find('<') {
+        let index = offset + start;
+        let tag = &html[index + 1..];
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let tag_end = tag
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(tag.len());
+        let tag = tag[..tag_end].to_ascii_lowercase();
+        let boundary = matches!(tag.as_str(), "a" | "dt" | "dd" | "li" | "dl" | "ul" | "ol");
+        if tag == name || boundary || (!closing && is_heading(&tag)) {
+            return index;
+        }
+        offset = index + 1;
+    }
+    html.len()
+}
+
+/// The text of an HTML fragment, without tags and with whitespace collapsed.
+fn html_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find('>')
+            .map_or("", |end| &rest[start + end + 1..]);
+    }
+    text.push_str(rest);
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decode the character references that occur in exports. Unknown references are kept as is.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(';').filter(|&end| end <= 10);
+        let c = end.and_then(|end| match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            reference => {
+                let code = match reference
+                    .strip_prefix("#x")
+                    .or_else(|| reference.strip_prefix("#X"))
+                {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => reference.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+        match (c, end) {
+            (Some(c), Some(end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use async_std::task::block_on;
+
+    #[test]
+    fn test_timestamp_units() {
+        let expected = Some(Utc.timestamp_opt(1_700_000_000, 0).unwrap());
+        assert_eq!(timestamp("1700000000"), expected);
+        assert_eq!(timestamp("1700000000000"), expected);
+        assert_eq!(timestamp("1700000000000000"), expected);
+        assert_eq!(timestamp("1700000000000000000"), None);
+        assert_eq!(timestamp("soon"), None);
+    }
+
+    fn urls(items: &[ImportItem]) -> Vec<&str> {
+        items.iter().map(|item| item.entry.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        let pocket = parse_pocket_html(
+            r#"<!DOCTYPE html><html><body>
+            <h1>Unread</h1>
+            <ul><li><a href="https://example.com/a" time_added="1700000000" tags="rust,web">A &amp; B</a></li></ul>
+            <h1>Read Archive</h1>
+            <ul><li><a href="https://example.com/b" time_added="1600000000" tags="">https://example.com/b</a></li></ul>
+            </body></html>"#,
+        );
+        assert_eq!(
+            urls(&pocket),
+            vec!["https://example.com/a", "https://example.com/b"]
+        );
+        assert_eq!(pocket[0].entry.title.as_deref(), Some("A & B"));
+        assert_eq!(
+            pocket[0].entry.tags,
+            Some(vec!["rust".to_owned(), "web".to_owned()])
+        );
+        assert_eq!(pocket[0].entry.archive, Some(false));
+        assert_eq!(pocket[1].entry.archive, Some(true));
+        assert_eq!(pocket[1].entry.tags, None);
+        assert_eq!(
+            pocket[0].added_at,
+            Some(Utc.timestamp_opt(1_700_000_000, 0).unwrap())
+        );
+
+        let pocket = parse_pocket_csv(
+            "title,url,time_added,tags,status\n\
+             \"Hello, world\",https://example.com/a,1700000000,rust|web,archive\n\
+             ,https://example.com/b,,,unread\n",
+        )
+        .unwrap();
+        assert_eq!(pocket[0].entry.title.as_deref(), Some("Hello, world"));
+        assert_eq!(
+            pocket[0].entry.tags,
+            Some(vec!["rust".to_owned(), "web".to_owned()])
+        );
+        assert_eq!(pocket[0].entry.archive, Some(true));
+        assert_eq!(pocket[1].entry.title, None);
+        assert_eq!(pocket[1].entry.archive, Some(false));
+
+        let instapaper = parse_instapaper_csv(
+            "URL,Title,Selection,Folder,Timestamp,Tags\n\
+             https://example.com/a,A,,Archive,1700000000,\"[\"\"rust\"\"]\"\n\
+             https://example.com/b,B,,Starred,1700000001,[]\n\
+             https://example.com/c,C,,Recipes,1700000002,\n",
+        )
+        .unwrap();
+        assert_eq!(instapaper[0].entry.archive, Some(true));
+        assert_eq!(instapaper[0].entry.tags, Some(vec!["rust".to_owned()]));
+        assert_eq!(instapaper[1].entry.starred, Some(true));
+        assert_eq!(instapaper[1].entry.tags, None);
+        assert_eq!(instapaper[2].entry.tags, Some(vec!["Recipes".to_owned()]));
+        assert!(matches!(
+            parse_instapaper_csv("Title,Folder\nA,Unread\n"),
+            Err(ImportError::MissingColumn("url"))
+        ));
+
+        let pinboard = parse_pinboard_json(
+            r#"[{"href":"https://example.com/a","description":"A","extended":"","meta":"x",
+                 "hash":"y","time":"2023-11-14T22:13:20Z","shared":"no","toread":"yes",
+                 "tags":"rust web"},
+                {"href":"https://example.com/b","description":"B","time":"2020-01-01T00:00:00Z",
+                 "toread":"no","tags":""}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            pinboard[0].entry.tags,
+            Some(vec!["rust".to_owned(), "web".to_owned()])
+        );
+        assert_eq!(pinboard[0].entry.archive, Some(false));
+        assert_eq!(
+            pinboard[0].added_at,
+            Some(Utc.timestamp_opt(1_700_000_000, 0).unwrap())
+        );
+        assert_eq!(pinboard[1].entry.archive, Some(true));
+
+        let netscape = parse_netscape_html(
+            "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n<TITLE>Bookmarks</TITLE>\n<H1>Bookmarks</H1>\n\
+             <DL><p>\n<DT><H3>Reading</H3>\n<DL><p>\n\
+             <DT><A HREF=\"https://example.com/a?x=1&amp;y=2\" ADD_DATE=\"1700000000\" \
+             TAGS=\"rust\" TOREAD=\"1\">A <b>bold</b> title</A>\n\
+             <DT><A HREF=https://example.com/b>B</A>\n\
+             <DT><A HREF=\"place:sort=8&amp;maxResults=10\">Recent</A>\n</DL><p>\n</DL><p>\n",
+        );
+        assert_eq!(
+            urls(&netscape),
+            vec!["https://example.com/a?x=1&y=2", "https://example.com/b"]
+        );
+        assert_eq!(netscape[0].entry.title.as_deref(), Some("A bold title"));
+        assert_eq!(netscape[0].entry.archive, Some(false));
+        assert_eq!(netscape[0].entry.tags, Some(vec!["rust".to_owned()]));
+        assert_eq!(netscape[1].entry.archive, None);
+        assert_eq!(netscape[1].added_at, None);
+    }
+
+    #[test]
+    fn test_parse_unclosed_links() {
+        let netscape = parse_netscape_html(
+            "<DL><p>\n<DT><A HREF=\"https://example.com/a\">A <b>bold</b> title\n\
+             <DT><A HREF=\"https://example.com/b\">B</A>\n<DT><H3>Folder\n\
+             <DT><A HREF=\"https://example.com/c\">C\n</DL><p>\n",
+        );
+        assert_eq!(
+            urls(&netscape),
+            vec![
+                "https://example.com/a",
+                "https://example.com/b",
+                "https://example.com/c"
+            ]
+        );
+        let titles: Vec<_> = netscape
+            .iter()
+            .map(|item| item.entry.title.as_deref())
+            .collect();
+        assert_eq!(titles, vec![Some("A bold title"), Some("B"), Some("C")]);
+
+        let pocket = parse_pocket_html(
+            r#"<ul><li><a href="https://example.com/a">A</li>
+            <li><a href="https://example.com/b">B</a></li></ul>"#,
+        );
+        assert_eq!(
+            urls(&pocket),
+            vec!["https://example.com/a", "https://example.com/b"]
+        );
+        assert_eq!(pocket[0].entry.title.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn test_import_entries() {
+        block_on(async {
+            let server = MockServer::new();
+            let existing = server.add_entry("https://example.com/saved", &[]).id;
+            let mut client = server.client();
+
+            let items = parse_netscape_html(
+                r#"<DT><A HREF="https://example.com/saved">Saved</A>
+                <DT><A HREF="https://example.com/new" TAGS="imported" TOREAD="0">New</A>
+                <DT><A HREF="https://example.com/new">New again</A>
+                <DT><A HREF="https://example.com/tagged" TAGS="a,b">Invalid</A>"#,
+            );
+            let mut invalid = items[3].clone();
+            invalid.entry.tags = Some(vec!["a,b".to_owned()]);
+            let items = vec![
+                items[0].clone(),
+                items[1].clone(),
+                items[2].clone(),
+                invalid,
+            ];
+
+            let mut report = ImportReport::default();
+            client.import_entries(&items, &mut report).await.unwrap();
+            assert_eq!(
+                report.duplicates,
+                BTreeMap::from([("https://example.com/saved".to_owned(), existing)])
+            );
+            assert_eq!(report.imported.len(), 1);
+            assert_eq!(
+                report.failed.keys().collect::<Vec<_>>(),
+                vec!["https://example.com/tagged"]
+            );
+            let id = report.imported["https://example.com/new"];
+            let entry = client.get_entry(id).await.unwrap();
+            assert!(entry.is_archived);
+            assert_eq!(entry.tags[0].label, "imported");
+
+            // Resuming sends nothing for urls that were handled already.
+            let mut resumed: ImportReport =
+                serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+            server.set_offline(true);
+            client.import_entries(&items, &mut resumed).await.unwrap();
+            assert_eq!(resumed, report);
+        })
+    }
+
+    #[test]
+    fn test_import_oldest_first() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let items = parse_netscape_html(
+                r#"<DT><A HREF="https://example.com/newer" ADD_DATE="1700000000">Newer</A>
+                <DT><A HREF="https://example.com/undated">Undated</A>
+                <DT><A HREF="https://example.com/older" ADD_DATE="1600000000">Older</A>"#,
+            );
+
+            let mut report = ImportReport::default();
+            client.import_entries(&items, &mut report).await.unwrap();
+            let mut imported: Vec<_> = report.imported.iter().collect();
+            imported.sort_by_key(|(_, id)| **id);
+            assert_eq!(
+                imported
+                    .iter()
+                    .map(|(url, _)| url.as_str())
+                    .collect::<Vec<_>>(),
+                vec![
+                    "https://example.com/older",
+                    "https://example.com/newer",
+                    "https://example.com/undated"
+                ]
+            );
+        })
+    }
+}
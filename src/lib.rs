@@ -62,10 +62,24 @@
 //! }
 //! ```
 
+pub mod cache;
 mod client;
+#[cfg(feature = "content")]
+pub mod content;
+#[cfg(feature = "epub")]
+pub mod epub;
 pub mod errors;
+pub mod export;
+pub mod feed;
+pub mod highlights;
+pub mod import;
+pub mod metrics;
+pub mod middleware;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod types;
 mod utils;
+pub mod validate;
 
 pub use crate::client::Client;
 pub use crate::errors::ClientError;
@@ -0,0 +1,305 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Metrics hooks for the requests sent by the client.
+//!
+//! Implement `Metrics` to forward the numbers to a metrics system (eg. Prometheus counters and
+//! histograms labelled by endpoint and status class), or use `InMemoryMetrics` to aggregate them
+//! in memory.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use wallabag_api::metrics::{InMemoryMetrics, StatusClass};
+//! # use wallabag_api::types::Config;
+//! use wallabag_api::Client;
+//!
+//! # let config = Config {
+//! #     client_id: "id".to_owned(),
+//! #     client_secret: "secret".to_owned(),
+//! #     username: "user".to_owned(),
+//! #     password: "pass".to_owned(),
+//! #     base_url: "https://example.com".to_owned(),
+//! # };
+//! let metrics = Arc::new(InMemoryMetrics::new());
+//! let client = Client::new(config).with_metrics(metrics.clone());
+//!
+//! // ... use the client ...
+//!
+//! println!("{} tag requests", metrics.requests("tags", StatusClass::Success));
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use surf::http::{Method, StatusCode};
+
+/// Class of the HTTP status of a response, for use as a low cardinality label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StatusClass {
+    /// 1xx
+    Informational,
+    /// 2xx
+    Success,
+    /// 3xx. The server responds with 304 Not Modified when reloading an entry didn't change it.
+    Redirection,
+    /// 4xx
+    ClientError,
+    /// 5xx
+    ServerError,
+    /// No response was received, eg. because of a network error.
+    NoResponse,
+}
+
+impl StatusClass {
+    pub fn from_status(status: Option<StatusCode>) -> Self {
+        match status {
+            None => StatusClass::NoResponse,
+            Some(status) if status.is_informational() => StatusClass::Informational,
+            Some(status) if status.is_success() => StatusClass::Success,
+            Some(status) if status.is_redirection() => StatusClass::Redirection,
+            Some(status) if status.is_client_error() => StatusClass::ClientError,
+            Some(_) => StatusClass::ServerError,
+        }
+    }
+
+    /// Label for the class, eg. `2xx`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusClass::Informational => "1xx",
+            StatusClass::Success => "2xx",
+            StatusClass::Redirection => "3xx",
+            StatusClass::ClientError => "4xx",
+            StatusClass::ServerError => "5xx",
+            StatusClass::NoResponse => "none",
+        }
+    }
+}
+
+impl fmt::Display for StatusClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Measurements of a single request sent to the server.
+#[derive(Debug, Clone)]
+pub struct RequestMetrics {
+    /// Name of the endpoint without ids, eg. `entry` for `/api/entries/{entry}.json` or `token`
+    /// for the oauth token endpoint.
+    pub endpoint: &'static str,
+
+    pub method: Method,
+
+    /// The status of the response, or `None` if no response was received.
+    pub status: Option<StatusCode>,
+
+    pub status_class: StatusClass,
+
+    /// Time from sending the request until the response headers were received. Includes the
+    /// time spent in middleware.
+    pub duration: Duration,
+
+    /// Size of the response body, if the server announced it with a `Content-Length` header.
+    pub bytes_received: Option<u64>,
+}
+
+/// Receives metrics from the client. All methods are called synchronously while a request is in
+/// progress, so implementations should be quick.
+pub trait Metrics: Send + Sync + 'static {
+    /// Called for every request sent to the server, including token requests and retries.
+    fn record_request(&self, request: &RequestMetrics);
+
+    /// Called when the client uses its refresh token to get a new access token.
+    fn record_token_refresh(&self) {}
+
+    /// Called when a request is sent again, eg. after refreshing an expired token.
+    fn record_retry(&self, _endpoint: &'static str) {}
+}
+
+/// The metrics sink of a client, if any.
+#[derive(Clone, Default)]
+pub(crate) struct MetricsHandle(Option<Arc<dyn Metrics>>);
+
+impl fmt::Debug for MetricsHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MetricsHandle({})", self.0.is_some())
+    }
+}
+
+impl MetricsHandle {
+    pub(crate) fn new(metrics: Arc<dyn Metrics>) -> Self {
+        Self(Some(metrics))
+    }
+
+    pub(crate) fn record_request(&self, request: &RequestMetrics) {
+        if let Some(ref metrics) = self.0 {
+            metrics.record_request(request);
+        }
+    }
+
+    pub(crate) fn record_token_refresh(&self) {
+        if let Some(ref metrics) = self.0 {
+            metrics.record_token_refresh();
+        }
+    }
+
+    pub(crate) fn record_retry(&self, endpoint: &'static str) {
+        if let Some(ref metrics) = self.0 {
+            metrics.record_retry(endpoint);
+        }
+    }
+}
+
+/// Aggregated numbers for all requests to one endpoint with one status class.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointStats {
+    pub requests: u64,
+    pub total_duration: Duration,
+    pub max_duration: Duration,
+    /// Sum of the known response body sizes.
+    pub bytes_received: u64,
+}
+
+#[derive(Debug, Default)]
+struct Aggregate {
+    requests: HashMap<(&'static str, StatusClass), EndpointStats>,
+    token_refreshes: u64,
+    retries: HashMap<&'static str, u64>,
+}
+
+/// A `Metrics` implementation that keeps counters in memory. Useful for tests, or to expose the
+/// numbers from a status page.
+#[derive(Debug, Default)]
+pub struct InMemoryMetrics {
+    aggregate: Mutex<Aggregate>,
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn aggregate(&self) -> MutexGuard<'_, Aggregate> {
+        self.aggregate.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Number of requests to an endpoint that got a response of the given class.
+    pub fn requests(&self, endpoint: &str, status_class: StatusClass) -> u64 {
+        self.stats(endpoint, status_class)
+            .map_or(0, |stats| stats.requests)
+    }
+
+    /// Aggregated numbers for an endpoint and status class, if any request was recorded.
+    pub fn stats(&self, endpoint: &str, status_class: StatusClass) -> Option<EndpointStats> {
+        self.aggregate()
+            .requests
+            .iter()
+            .find(|((e, c), _)| *e == endpoint && *c == status_class)
+            .map(|(_, stats)| stats.clone())
+    }
+
+    /// Number of requests to all endpoints.
+    pub fn total_requests(&self) -> u64 {
+        self.aggregate()
+            .requests
+            .values()
+            .map(|stats| stats.requests)
+            .sum()
+    }
+
+    /// Number of times the access token was refreshed.
+    pub fn token_refreshes(&self) -> u64 {
+        self.aggregate().token_refreshes
+    }
+
+    /// Number of retried requests to an endpoint.
+    pub fn retries(&self, endpoint: &str) -> u64 {
+        self.aggregate()
+            .retries
+            .iter()
+            .find(|(e, _)| **e == endpoint)
+            .map_or(0, |(_, retries)| *retries)
+    }
+
+    /// Reset all numbers to zero.
+    pub fn reset(&self) {
+        *self.aggregate() = Aggregate::default();
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn record_request(&self, request: &RequestMetrics) {
+        let mut aggregate = self.aggregate();
+        let stats = aggregate
+            .requests
+            .entry((request.endpoint, request.status_class))
+            .or_default();
+        stats.requests += 1;
+        stats.total_duration += request.duration;
+        stats.max_duration = stats.max_duration.max(request.duration);
+        stats.bytes_received += request.bytes_received.unwrap_or(0);
+    }
+
+    fn record_token_refresh(&self) {
+        self.aggregate().token_refreshes += 1;
+    }
+
+    fn record_retry(&self, endpoint: &'static str) {
+        *self.aggregate().retries.entry(endpoint).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use async_std::task::block_on;
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(
+            StatusClass::from_status(Some(StatusCode::Ok)),
+            StatusClass::Success
+        );
+        assert_eq!(
+            StatusClass::from_status(Some(StatusCode::NotModified)),
+            StatusClass::Redirection
+        );
+        assert_eq!(
+            StatusClass::from_status(Some(StatusCode::BadGateway)),
+            StatusClass::ServerError
+        );
+        assert_eq!(StatusClass::from_status(None), StatusClass::NoResponse);
+    }
+
+    #[test]
+    fn test_in_memory_metrics_from_client() {
+        block_on(async {
+            let server = MockServer::new();
+            let metrics = Arc::new(InMemoryMetrics::new());
+            let mut client = server.client().with_metrics(metrics.clone());
+
+            client.get_tags().await.unwrap();
+            assert!(client.get_entry(1).await.is_err());
+            server.expire_tokens();
+            client.get_tags().await.unwrap();
+
+            assert_eq!(metrics.requests("token", StatusClass::Success), 2);
+            assert_eq!(metrics.requests("tags", StatusClass::Success), 2);
+            assert_eq!(metrics.requests("tags", StatusClass::ClientError), 1);
+            assert_eq!(metrics.requests("entry", StatusClass::ClientError), 1);
+            assert_eq!(metrics.total_requests(), 6);
+            assert_eq!(metrics.token_refreshes(), 1);
+            assert_eq!(metrics.retries("tags"), 1);
+            assert!(
+                metrics
+                    .stats("tags", StatusClass::Success)
+                    .unwrap()
+                    .bytes_received
+                    > 0
+            );
+        })
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Middleware hooks around every request sent by the client, including the requests for oauth
+//! tokens.
+//!
+//! Middleware uses the surf `Middleware` trait. Each layer gets the outgoing request and can
+//! modify it, pass it on with `next.run(req, client)`, and inspect or modify the response. A
+//! layer can also short-circuit by returning a response without calling `next`.
+//!
+//! Layers are added with `Client::with_middleware` and run in the order they were added: the
+//! first layer added sees the request first and the response last.
+//!
+//! ```
+//! use wallabag_api::middleware::{LogTimings, Middleware, Next, SetHeaders};
+//! # use wallabag_api::types::Config;
+//! use wallabag_api::Client;
+//!
+//! /// Authenticate to a corporate gateway.
+//! struct Gateway {
+//!     token: String,
+//! }
+//!
+//! #[async_trait::async_trait]
+//! impl Middleware for Gateway {
+//!     async fn handle(
+//!         &self,
+//!         mut req: surf::Request,
+//!         client: surf::Client,
+//!         next: Next<'_>,
+//!     ) -> surf::Result<surf::Response> {
+//!         req.insert_header("X-Gateway-Token", self.token.as_str());
+//!         next.run(req, client).await
+//!     }
+//! }
+//!
+//! # let config = Config {
+//! #     client_id: "id".to_owned(),
+//! #     client_secret: "secret".to_owned(),
+//! #     username: "user".to_owned(),
+//! #     password: "pass".to_owned(),
+//! #     base_url: "https://example.com".to_owned(),
+//! # };
+//! let client = Client::new(config)
+//!     .with_middleware(LogTimings)
+//!     .with_middleware(SetHeaders::new().header("X-Request-Source", "dashboard"))
+//!     .with_middleware(Gateway {
+//!         token: "secret".to_owned(),
+//!     });
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use log::debug;
+use surf::{Request, Response};
+
+pub use surf::middleware::{Middleware, Next};
+
+/// The ordered list of middleware added to a client.
+#[derive(Clone, Default)]
+pub(crate) struct MiddlewareStack {
+    layers: Vec<Arc<dyn Middleware>>,
+}
+
+impl fmt::Debug for MiddlewareStack {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MiddlewareStack({} layers)", self.layers.len())
+    }
+}
+
+impl MiddlewareStack {
+    pub(crate) fn push<M: Middleware>(&mut self, middleware: M) {
+        self.layers.push(Arc::new(middleware));
+    }
+
+    /// Attach all layers to a request, in order.
+    pub(crate) fn apply(&self, request: &mut Request) {
+        for layer in &self.layers {
+            request.middleware(SharedLayer(layer.clone()));
+        }
+    }
+}
+
+/// Lets a layer be shared between all requests sent by a client.
+struct SharedLayer(Arc<dyn Middleware>);
+
+#[async_trait]
+impl Middleware for SharedLayer {
+    async fn handle(
+        &self,
+        req: Request,
+        client: surf::Client,
+        next: Next<'_>,
+    ) -> surf::Result<Response> {
+        self.0.handle(req, client, next).await
+    }
+}
+
+/// Middleware that sets a fixed list of headers on every request. Useful for tracing headers or
+/// headers required by a gateway.
+#[derive(Debug, Clone, Default)]
+pub struct SetHeaders {
+    headers: Vec<(String, String)>,
+}
+
+impl SetHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a header to set. A header that is already present on the request is replaced.
+    pub fn header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+#[async_trait]
+impl Middleware for SetHeaders {
+    async fn handle(
+        &self,
+        mut req: Request,
+        client: surf::Client,
+        next: Next<'_>,
+    ) -> surf::Result<Response> {
+        for (name, value) in &self.headers {
+            req.insert_header(name.as_str(), value.as_str());
+        }
+        next.run(req, client).await
+    }
+}
+
+/// Middleware that logs the method, path, status and duration of every request at debug level.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogTimings;
+
+#[async_trait]
+impl Middleware for LogTimings {
+    async fn handle(
+        &self,
+        req: Request,
+        client: surf::Client,
+        next: Next<'_>,
+    ) -> surf::Result<Response> {
+        let method = req.method();
+        let path = req.url().path().to_owned();
+        let start = Instant::now();
+        let result = next.run(req, client).await;
+        match result {
+            Ok(ref res) => debug!(
+                "{} {} -> {} in {:?}",
+                method,
+                path,
+                res.status(),
+                start.elapsed()
+            ),
+            Err(ref e) => debug!("{} {} failed in {:?}: {}", method, path, start.elapsed(), e),
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use async_std::task::block_on;
+    use std::sync::Mutex;
+    use surf::http::{self, StatusCode};
+    use surf::Body;
+
+    /// Logs the path of each request and the value of a header, tagged with a name.
+    struct Spy {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Spy {
+        async fn handle(
+            &self,
+            req: Request,
+            client: surf::Client,
+            next: Next<'_>,
+        ) -> surf::Result<Response> {
+            let header = req.header("X-Test").map(|value| value.as_str().to_owned());
+            self.log.lock().unwrap().push(format!(
+                "{} {} {:?}",
+                self.name,
+                req.url().path(),
+                header
+            ));
+            next.run(req, client).await
+        }
+    }
+
+    /// Answers the version endpoint without contacting the server.
+    struct FakeVersion;
+
+    #[async_trait]
+    impl Middleware for FakeVersion {
+        async fn handle(
+            &self,
+            req: Request,
+            client: surf::Client,
+            next: Next<'_>,
+        ) -> surf::Result<Response> {
+            if req.url().path() == "/api/version.json" {
+                let mut res = http::Response::new(StatusCode::Ok);
+                res.set_body(Body::from_json(&"0.0.1")?);
+                return Ok(res.into());
+            }
+            next.run(req, client).await
+        }
+    }
+
+    #[test]
+    fn test_middleware_order_and_token_requests() {
+        block_on(async {
+            let server = MockServer::new();
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let mut client = server
+                .client()
+                .with_middleware(Spy {
+                    name: "first",
+                    log: log.clone(),
+                })
+                .with_middleware(SetHeaders::new().header("X-Test", "set"))
+                .with_middleware(Spy {
+                    name: "second",
+                    log: log.clone(),
+                });
+
+            client.get_tags().await.unwrap();
+
+            assert_eq!(
+                *log.lock().unwrap(),
+                vec![
+                    "first /oauth/v2/token None",
+                    "second /oauth/v2/token Some(\"set\")",
+                    "first /api/tags.json None",
+                    "second /api/tags.json Some(\"set\")",
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_middleware_short_circuit() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client().with_middleware(FakeVersion);
+
+            assert_eq!(client.get_api_version().await.unwrap(), "0.0.1");
+            assert_eq!(server.requests(), vec!["POST /oauth/v2/token"]);
+        })
+    }
+}
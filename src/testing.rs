@@ -0,0 +1,37 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Helpers for testing code that uses the client without a live wallabag instance. Enabled with
+//! the `testing` feature.
+//!
+//! `MockServer` is an in-process fake wallabag server. It implements the API endpoints used by
+//! the client on top of in-memory data, so no network access is needed.
+//!
+//! `Recorder` and `Replayer` capture interactions with a real server to a cassette file and serve
+//! them back later. This is useful for regression tests against unusual server responses.
+//!
+//! ```
+//! use wallabag_api::testing::MockServer;
+//! use wallabag_api::types::NewEntry;
+//!
+//! async fn run_example() {
+//!     let server = MockServer::new();
+//!     let mut client = server.client();
+//!
+//!     let entry = client
+//!         .create_entry(&NewEntry::new_with_url("https://example.com/".to_owned()))
+//!         .await
+//!         .unwrap();
+//!     assert_eq!(client.get_entry(entry.id).await.unwrap().id, entry.id);
+//! }
+//!
+//! async_std::task::block_on(run_example())
+//! ```
+
+mod cassette;
+mod mock_server;
+
+pub use self::cassette::{
+    Cassette, Interaction, RecordedBody, RecordedRequest, RecordedResponse, Recorder, Replayer,
+};
+pub use self::mock_server::MockServer;
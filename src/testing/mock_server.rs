@@ -0,0 +1,1165 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use surf::http::{headers, mime, mime::Mime, Method, Request, Response, StatusCode, Url};
+use surf::{Body, HttpClient};
+
+use crate::types::{
+    Annotation, AnnotationId, Authors, Config, Entry, EntryId, Range, Tag, TagId, ID,
+};
+use crate::Client;
+
+/// Base url used by the clients created by `MockServer`. Requests never leave the process, so the
+/// host doesn't need to exist.
+const BASE_URL: &str = "http://wallabag.test";
+
+/// An in-process fake wallabag server.
+///
+/// Implements the OAuth token grants (password and refresh token), entries with pagination,
+/// tags, annotations and exports. Data is stored in memory and shared between clones, so a test
+/// can keep a handle to the server to seed data or inspect requests while a client uses it.
+/// Other pages can be served with `MockServer::respond`.
+///
+/// Use `MockServer::client` to get a `Client` that sends all requests to this server.
+#[derive(Debug, Clone)]
+pub struct MockServer {
+    state: Arc<Mutex<State>>,
+}
+
+/// All data stored by the fake server.
+#[derive(Debug)]
+struct State {
+    config: Config,
+    /// Access token -> whether the token is expired.
+    access_tokens: HashMap<String, bool>,
+    refresh_tokens: Vec<String>,
+    next_token: u32,
+    entries: BTreeMap<i64, Entry>,
+    tags: Vec<Tag>,
+    next_entry_id: i64,
+    next_tag_id: i64,
+    next_annotation_id: i64,
+    /// Entry id -> content returned on the next reload of that entry.
+    reload_content: HashMap<i64, String>,
+    /// Path, optionally with a query -> fixed response that replaces the fake API.
+    routes: HashMap<String, Route>,
+    offline: bool,
+    requests: Vec<String>,
+}
+
+/// A fixed response served by `MockServer::respond`.
+#[derive(Debug, Clone)]
+struct Route {
+    status: StatusCode,
+    content_type: Option<Mime>,
+    body: Vec<u8>,
+}
+
+impl Route {
+    fn response(&self) -> Response {
+        let mut response = Response::new(self.status);
+        response.set_body(Body::from_bytes(self.body.clone()));
+        if let Some(ref content_type) = self.content_type {
+            response.set_content_type(content_type.clone());
+        }
+        response
+    }
+}
+
+impl Default for MockServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockServer {
+    /// Create an empty server. The credentials accepted by the server are available through
+    /// `MockServer::config`.
+    pub fn new() -> Self {
+        let config = Config {
+            client_id: "mock_client_id".to_owned(),
+            client_secret: "mock_client_secret".to_owned(),
+            username: "wallabag".to_owned(),
+            password: "mock_password".to_owned(),
+            base_url: BASE_URL.to_owned(),
+        };
+        Self {
+            state: Arc::new(Mutex::new(State {
+                config,
+                access_tokens: HashMap::new(),
+                refresh_tokens: Vec::new(),
+                next_token: 1,
+                entries: BTreeMap::new(),
+                tags: Vec::new(),
+                next_entry_id: 1,
+                next_tag_id: 1,
+                next_annotation_id: 1,
+                reload_content: HashMap::new(),
+                routes: HashMap::new(),
+                offline: false,
+                requests: Vec::new(),
+            })),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // A panic while holding the lock means a test already failed; keep serving anyway.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A config with the credentials accepted by this server.
+    pub fn config(&self) -> Config {
+        self.state().config.clone()
+    }
+
+    /// Build a client that is logged in with valid credentials and sends all requests to this
+    /// server.
+    pub fn client(&self) -> Client {
+        self.client_with_config(self.config())
+    }
+
+    /// Build a client with custom config that sends all requests to this server. Useful for
+    /// testing invalid credentials. The `base_url` of the config is ignored.
+    pub fn client_with_config(&self, config: Config) -> Client {
+        let config = Config {
+            base_url: BASE_URL.to_owned(),
+            ..config
+        };
+        Client::new(config).with_surf_client(surf::Client::with_http_client(self.clone()))
+    }
+
+    /// Store an entry directly, without going through the API. The id is assigned by the server
+    /// and tags are created as needed. Returns the stored entry.
+    pub fn add_entry(&self, url: &str, tags: &[&str]) -> Entry {
+        let mut state = self.state();
+        let data = json!({ "url": url, "tags": tags });
+        let id = state.create_entry(&data);
+        state.entries[&id].clone()
+    }
+
+    /// Mark all access tokens handed out so far as expired. The next request using one of them
+    /// gets the expired token error, and the refresh token can be used to get a new one.
+    pub fn expire_tokens(&self) {
+        for expired in self.state().access_tokens.values_mut() {
+            *expired = true;
+        }
+    }
+
+    /// Set the content that the server "fetches" the next time the entry is reloaded. Without
+    /// this, reloading an entry responds with not modified.
+    pub fn set_reload_content<T: Into<EntryId>>(&self, entry_id: T, content: &str) {
+        self.state()
+            .reload_content
+            .insert(entry_id.into().as_int(), content.to_owned());
+    }
+
+    /// Answer requests for `path` with a fixed response instead of the fake API, on any host.
+    /// Useful for pages the fake API doesn't implement, like the exports of the web interface or
+    /// preview pictures, and for unusual server responses. A path with a query, eg.
+    /// `/export/tag_entries.json?tag=rust`, only matches requests with exactly that query.
+    pub fn respond(&self, path: &str, status: StatusCode, content_type: Option<Mime>, body: &[u8]) {
+        let route = Route {
+            status,
+            content_type,
+            body: body.to_vec(),
+        };
+        self.state().routes.insert(path.to_owned(), route);
+    }
+
+    /// While offline, every request fails before it reaches the server, as if the network is
+    /// down. These requests are not recorded.
+    pub fn set_offline(&self, offline: bool) {
+        self.state().offline = offline;
+    }
+
+    /// All requests received so far, formatted as method and path. Eg. `GET /api/tags.json`.
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+}
+
+#[async_trait]
+impl HttpClient for MockServer {
+    async fn send(&self, mut req: Request) -> Result<Response, surf::Error> {
+        if self.state().offline {
+            return Err(surf::Error::from_str(
+                StatusCode::ServiceUnavailable,
+                "network is down",
+            ));
+        }
+        let body = req.body_string().await?;
+        let body: Value = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(&body).unwrap_or(Value::Null)
+        };
+        let bearer = req
+            .header(headers::AUTHORIZATION)
+            .and_then(|value| value.as_str().strip_prefix("Bearer "))
+            .map(str::to_owned);
+
+        let mut state = self.state();
+        state
+            .requests
+            .push(format!("{} {}", req.method(), req.url().path()));
+        if let Some(route) = state.route(req.url()) {
+            return Ok(route.response());
+        }
+        Ok(state.handle(req.method(), req.url(), bearer, &body))
+    }
+}
+
+/// Build a response with a json body.
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response {
+    let mut response = Response::new(status);
+    response.set_body(Body::from_json(body).expect("mock data must serialize"));
+    response
+}
+
+/// Build a 404 response in the format used by the wallabag API.
+fn not_found() -> Response {
+    json_response(
+        StatusCode::NotFound,
+        &json!({ "error": { "code": 404, "message": "Not Found" } }),
+    )
+}
+
+fn oauth_error(status: StatusCode, error: &str, description: &str) -> Response {
+    json_response(
+        status,
+        &json!({ "error": error, "error_description": description }),
+    )
+}
+
+fn timestamp(t: DateTime<Utc>) -> Value {
+    Value::String(t.to_rfc3339())
+}
+
+/// Read a string field from a json request body.
+fn get_str(data: &Value, key: &str) -> Option<String> {
+    data.get(key).and_then(Value::as_str).map(str::to_owned)
+}
+
+/// Read a pseudo-bool field: accepts booleans, 0/1 integers and strings of either.
+fn get_bool(data: &Value, key: &str) -> Option<bool> {
+    match data.get(key)? {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => n.as_i64().map(|n| n != 0),
+        Value::String(s) => parse_bool(s),
+        _ => None,
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s {
+        "1" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Read a list of tag labels given as either a json array or a comma separated string.
+fn get_tags(data: &Value, key: &str) -> Option<Vec<String>> {
+    let labels = match data.get(key)? {
+        Value::Array(values) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect::<Vec<_>>(),
+        Value::String(s) => s.split(',').map(str::to_owned).collect(),
+        _ => return None,
+    };
+    Some(
+        labels
+            .into_iter()
+            .map(|label| label.trim().to_owned())
+            .filter(|label| !label.is_empty())
+            .collect(),
+    )
+}
+
+/// Read a string field of a patch. Like the server, an empty string leaves the field unchanged.
+fn get_patch_str(data: &Value, key: &str) -> Option<String> {
+    get_str(data, key).filter(|value| !value.is_empty())
+}
+
+fn get_date(data: &Value, key: &str) -> Option<DateTime<Utc>> {
+    data.get(key)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+/// Turn a tag label into a slug the way the server does.
+fn slugify(label: &str) -> String {
+    label
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parse an id from a path segment such as `12.json`.
+fn parse_id(segment: &str) -> Option<i64> {
+    segment
+        .strip_suffix(".json")
+        .unwrap_or(segment)
+        .parse()
+        .ok()
+}
+
+impl State {
+    /// The fixed response for a url, preferring a route with the exact query.
+    fn route(&self, url: &Url) -> Option<&Route> {
+        let exact = url
+            .query()
+            .and_then(|query| self.routes.get(&format!("{}?{}", url.path(), query)));
+        exact.or_else(|| self.routes.get(url.path()))
+    }
+
+    fn handle(
+        &mut self,
+        method: Method,
+        url: &Url,
+        bearer: Option<String>,
+        body: &Value,
+    ) -> Response {
+        let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        let param = |key: &str| {
+            query
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        let segments: Vec<&str> = url.path().trim_start_matches('/').split('/').collect();
+
+        if segments == ["oauth", "v2", "token"] && method == Method::Post {
+            return self.token(body);
+        }
+        if segments == ["api", "user.json"] && method == Method::Put {
+            return self.register(body);
+        }
+
+        if let Some(response) = self.reject_token(bearer) {
+            return response;
+        }
+
+        match (method, segments.as_slice()) {
+            (Method::Get, ["api", "version.json"]) => json_response(StatusCode::Ok, &"2.6.9"),
+            (Method::Get, ["api", "user.json"]) => self.user(),
+            (Method::Get, ["api", "entries.json"]) => self.list_entries(&param),
+            (Method::Post, ["api", "entries.json"]) => {
+                if get_str(body, "url").is_none() {
+                    return json_response(
+                        StatusCode::BadRequest,
+                        &json!({ "error": { "code": 400, "message": "url is required" } }),
+                    );
+                }
+                let id = self.create_entry(body);
+                json_response(StatusCode::Ok, &self.entries[&id])
+            }
+            (Method::Get, ["api", "entries", "exists.json"]) => self.exists(&query),
+            (method, ["api", "entries", id]) => match parse_id(id) {
+                Some(id) if self.entries.contains_key(&id) => self.entry(method, id, body),
+                _ => not_found(),
+            },
+            (method, ["api", "entries", id, action]) => match parse_id(id) {
+                Some(id) if self.entries.contains_key(&id) => {
+                    self.entry_action(method, id, action, body)
+                }
+                _ => not_found(),
+            },
+            (Method::Delete, ["api", "entries", id, "tags", tag]) => {
+                match (parse_id(id), parse_id(tag)) {
+                    (Some(id), Some(tag)) if self.entries.contains_key(&id) => {
+                        if !self.tags.iter().any(|t| t.id.as_int() == tag) {
+                            return not_found();
+                        }
+                        let entry = self.entries.get_mut(&id).unwrap();
+                        entry.tags.retain(|t| t.id.as_int() != tag);
+                        entry.updated_at = Utc::now();
+                        json_response(StatusCode::Ok, &self.entries[&id])
+                    }
+                    _ => not_found(),
+                }
+            }
+            (Method::Get, ["api", "tags.json"]) => json_response(StatusCode::Ok, &self.tags),
+            (Method::Delete, ["api", "tag", "label.json"]) => {
+                match param("tag").and_then(|label| self.tag_position(label)) {
+                    Some(index) => {
+                        let tag = self.delete_tag(index);
+                        json_response(StatusCode::Ok, &tag)
+                    }
+                    None => not_found(),
+                }
+            }
+            (Method::Delete, ["api", "tags", "label.json"]) => {
+                let labels = param("tags").unwrap_or_default();
+                let mut deleted = Vec::new();
+                for label in labels.split(',') {
+                    if let Some(index) = self.tag_position(label.trim()) {
+                        deleted.push(self.delete_tag(index));
+                    }
+                }
+                if deleted.is_empty() {
+                    not_found()
+                } else {
+                    json_response(StatusCode::Ok, &deleted)
+                }
+            }
+            (Method::Delete, ["api", "tags", id]) => {
+                match parse_id(id).and_then(|id| self.tags.iter().position(|t| *t.id == id)) {
+                    Some(index) => {
+                        let tag = self.delete_tag(index);
+                        json_response(StatusCode::Ok, &tag)
+                    }
+                    None => not_found(),
+                }
+            }
+            (method, ["api", "annotations", id]) => match parse_id(id) {
+                Some(id) => self.annotation(method, id, body),
+                None => not_found(),
+            },
+            _ => not_found(),
+        }
+    }
+
+    /// Check the bearer token of a request to the api. Returns the error response if the token
+    /// isn't valid.
+    fn reject_token(&self, bearer: Option<String>) -> Option<Response> {
+        let token = match bearer {
+            Some(token) => token,
+            None => {
+                return Some(oauth_error(
+                    StatusCode::Unauthorized,
+                    "access_denied",
+                    "OAuth2 authentication required",
+                ))
+            }
+        };
+        match self.access_tokens.get(&token) {
+            Some(false) => None,
+            Some(true) => Some(oauth_error(
+                StatusCode::Unauthorized,
+                "invalid_grant",
+                "The access token provided has expired.",
+            )),
+            None => Some(oauth_error(
+                StatusCode::Unauthorized,
+                "invalid_grant",
+                "The access token provided is invalid.",
+            )),
+        }
+    }
+
+    /// Handle both the password grant and the refresh token grant.
+    fn token(&mut self, body: &Value) -> Response {
+        if get_str(body, "client_id").as_ref() != Some(&self.config.client_id)
+            || get_str(body, "client_secret").as_ref() != Some(&self.config.client_secret)
+        {
+            return oauth_error(
+                StatusCode::BadRequest,
+                "invalid_client",
+                "The client credentials are invalid",
+            );
+        }
+
+        match get_str(body, "grant_type").as_deref() {
+            Some("password") => {
+                if get_str(body, "username").as_ref() != Some(&self.config.username)
+                    || get_str(body, "password").as_ref() != Some(&self.config.password)
+                {
+                    return oauth_error(
+                        StatusCode::BadRequest,
+                        "invalid_grant",
+                        "Invalid username and password combination",
+                    );
+                }
+            }
+            Some("refresh_token") => {
+                let refresh_token = get_str(body, "refresh_token").unwrap_or_default();
+                match self.refresh_tokens.iter().position(|t| *t == refresh_token) {
+                    // refresh tokens can only be used once
+                    Some(index) => {
+                        self.refresh_tokens.remove(index);
+                    }
+                    None => {
+                        return oauth_error(
+                            StatusCode::BadRequest,
+                            "invalid_grant",
+                            "Invalid refresh token",
+                        )
+                    }
+                }
+            }
+            _ => {
+                return oauth_error(
+                    StatusCode::BadRequest,
+                    "unsupported_grant_type",
+                    "Invalid grant_type parameter or parameter missing",
+                )
+            }
+        }
+
+        let n = self.next_token;
+        self.next_token += 1;
+        let access_token = format!("access-token-{}", n);
+        let refresh_token = format!("refresh-token-{}", n);
+        self.access_tokens.insert(access_token.clone(), false);
+        self.refresh_tokens.push(refresh_token.clone());
+        json_response(
+            StatusCode::Ok,
+            &json!({
+                "access_token": access_token,
+                "expires_in": 3600,
+                "token_type": "bearer",
+                "scope": null,
+                "refresh_token": refresh_token,
+            }),
+        )
+    }
+
+    fn user(&self) -> Response {
+        json_response(
+            StatusCode::Ok,
+            &json!({
+                "id": 1,
+                "username": self.config.username,
+                "email": format!("{}@wallabag.test", self.config.username),
+                "created_at": timestamp(Utc.timestamp_opt(0, 0).unwrap()),
+                "updated_at": timestamp(Utc.timestamp_opt(0, 0).unwrap()),
+            }),
+        )
+    }
+
+    fn register(&self, body: &Value) -> Response {
+        let now = timestamp(Utc::now());
+        json_response(
+            StatusCode::Ok,
+            &json!({
+                "id": 2,
+                "username": get_str(body, "username"),
+                "email": get_str(body, "email"),
+                "created_at": now,
+                "updated_at": now,
+                "default_client": {
+                    "client_id": "registered_client_id",
+                    "client_secret": "registered_client_secret",
+                    "name": get_str(body, "client_name"),
+                },
+            }),
+        )
+    }
+
+    /// Find an existing tag by label, or create it.
+    fn tag_for_label(&mut self, label: &str) -> Tag {
+        if let Some(index) = self.tag_position(label) {
+            return self.tags[index].clone();
+        }
+        let tag = Tag {
+            id: TagId(self.next_tag_id),
+            label: label.to_owned(),
+            slug: slugify(label),
+        };
+        self.next_tag_id += 1;
+        self.tags.push(tag.clone());
+        tag
+    }
+
+    fn tag_position(&self, label: &str) -> Option<usize> {
+        self.tags.iter().position(|t| t.label == label)
+    }
+
+    /// Delete a tag globally, removing it from all entries.
+    fn delete_tag(&mut self, index: usize) -> Tag {
+        let tag = self.tags.remove(index);
+        for entry in self.entries.values_mut() {
+            entry.tags.retain(|t| t.id != tag.id);
+        }
+        tag
+    }
+
+    fn add_tags(&mut self, id: i64, labels: &[String]) {
+        for label in labels {
+            let tag = self.tag_for_label(label);
+            let entry = self.entries.get_mut(&id).unwrap();
+            if !entry.tags.iter().any(|t| t.id == tag.id) {
+                entry.tags.push(tag);
+            }
+        }
+    }
+
+    /// Create an entry from the fields of a `NewEntry`. Returns the new id.
+    fn create_entry(&mut self, data: &Value) -> i64 {
+        let url = get_str(data, "url").unwrap_or_default();
+        // The server updates the entry if the url is saved already. Tags are added to the
+        // existing ones, unlike when patching.
+        let existing = self
+            .entries
+            .values()
+            .find(|entry| entry.url.as_deref() == Some(url.as_str()))
+            .map(|entry| entry.id.0);
+        if let Some(id) = existing {
+            if let Some(labels) = get_tags(data, "tags") {
+                self.add_tags(id, &labels);
+            }
+            let mut fields = data.clone();
+            if let Some(fields) = fields.as_object_mut() {
+                fields.remove("tags");
+            }
+            self.patch_entry(id, &fields);
+            return id;
+        }
+        let domain_name = Url::parse(&url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_owned));
+        let content = get_str(data, "content")
+            .unwrap_or_else(|| format!("<p>Content fetched from {}</p>", url));
+        let is_starred = get_bool(data, "starred").unwrap_or(false);
+        let now = Utc::now();
+
+        let id = self.next_entry_id;
+        self.next_entry_id += 1;
+        let entry = Entry {
+            annotations: Some(vec![]),
+            reading_time: reading_time(&content),
+            content: Some(content),
+            created_at: now,
+            domain_name,
+            headers: Some(HashMap::from([(
+                "content-type".to_owned(),
+                "text/html".to_owned(),
+            )])),
+            http_status: Some("200".to_owned()),
+            id: EntryId(id),
+            is_archived: get_bool(data, "archive").unwrap_or(false),
+            is_public: get_bool(data, "public").unwrap_or(false),
+            is_starred,
+            language: get_str(data, "language"),
+            mimetype: Some("text/html".to_owned()),
+            origin_url: get_str(data, "origin_url"),
+            preview_picture: get_str(data, "preview_picture"),
+            published_at: get_date(data, "published_at"),
+            published_by: get_str(data, "authors").map(|authors| Authors::from(authors.as_str())),
+            starred_at: if is_starred { Some(now) } else { None },
+            tags: vec![],
+            title: Some(get_str(data, "title").unwrap_or_else(|| url.clone())),
+            uid: None,
+            updated_at: now,
+            url: Some(url),
+            user_email: format!("{}@wallabag.test", self.config.username),
+            user_id: ID(1),
+            user_name: self.config.username.clone(),
+        };
+        self.entries.insert(id, entry);
+        if let Some(labels) = get_tags(data, "tags") {
+            self.add_tags(id, &labels);
+        }
+        id
+    }
+
+    /// Apply the fields of a `PatchEntry`. Tags replace the tags of the entry, but an empty list
+    /// is ignored, like the server does.
+    fn patch_entry(&mut self, id: i64, data: &Value) {
+        if let Some(labels) = get_tags(data, "tags").filter(|labels| !labels.is_empty()) {
+            self.entries.get_mut(&id).unwrap().tags.clear();
+            self.add_tags(id, &labels);
+        }
+
+        let now = Utc::now();
+        let entry = self.entries.get_mut(&id).unwrap();
+        if let Some(title) = get_patch_str(data, "title") {
+            entry.title = Some(title);
+        }
+        if let Some(archive) = get_bool(data, "archive") {
+            entry.is_archived = archive;
+        }
+        if let Some(starred) = get_bool(data, "starred") {
+            if starred && !entry.is_starred {
+                entry.starred_at = Some(now);
+            } else if !starred {
+                entry.starred_at = None;
+            }
+            entry.is_starred = starred;
+        }
+        if let Some(public) = get_bool(data, "public") {
+            entry.is_public = public;
+        }
+        if let Some(content) = get_patch_str(data, "content") {
+            entry.reading_time = reading_time(&content);
+            entry.content = Some(content);
+        }
+        if let Some(language) = get_patch_str(data, "language") {
+            entry.language = Some(language);
+        }
+        if let Some(preview_picture) = get_patch_str(data, "preview_picture") {
+            entry.preview_picture = Some(preview_picture);
+        }
+        if let Some(published_at) = get_date(data, "published_at") {
+            entry.published_at = Some(published_at);
+        }
+        if let Some(authors) = get_patch_str(data, "authors") {
+            entry.published_by = Some(Authors::from(authors.as_str()));
+        }
+        if let Some(origin_url) = get_patch_str(data, "origin_url") {
+            entry.origin_url = Some(origin_url);
+        }
+        entry.updated_at = now;
+    }
+
+    /// Handle the paginated and filtered list of entries.
+    fn list_entries<'a>(&self, param: &dyn Fn(&str) -> Option<&'a str>) -> Response {
+        let per_page = param("perPage")
+            .and_then(|p| p.parse::<usize>().ok())
+            .unwrap_or(30);
+        let page = param("page")
+            .and_then(|p| p.parse::<usize>().ok())
+            .unwrap_or(1);
+        if per_page == 0 || page == 0 {
+            return not_found();
+        }
+
+        let archive = param("archive").and_then(parse_bool);
+        let starred = param("starred").and_then(parse_bool);
+        let public = param("public").and_then(parse_bool);
+        let since = param("since")
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(0);
+        let tags: Vec<&str> = param("tags")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        let mut entries: Vec<&Entry> = self
+            .entries
+            .values()
+            .filter(|e| archive.is_none_or(|a| e.is_archived == a))
+            .filter(|e| starred.is_none_or(|s| e.is_starred == s))
+            .filter(|e| public.is_none_or(|p| e.is_public == p))
+            .filter(|e| e.updated_at.timestamp() >= since)
+            .filter(|e| {
+                tags.iter()
+                    .all(|label| e.tags.iter().any(|t| t.label == *label))
+            })
+            .collect();
+        match param("sort") {
+            Some("updated") => entries.sort_by_key(|e| (e.updated_at, e.id.as_int())),
+            _ => entries.sort_by_key(|e| (e.created_at, e.id.as_int())),
+        }
+        if param("order") != Some("asc") {
+            entries.reverse();
+        }
+
+        let total = entries.len();
+        let pages = std::cmp::max(1, total.div_ceil(per_page));
+        if page > pages {
+            return not_found();
+        }
+        let items: Vec<&Entry> = entries
+            .into_iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .collect();
+
+        json_response(
+            StatusCode::Ok,
+            &json!({
+                "page": page,
+                "limit": per_page,
+                "pages": pages,
+                "total": total,
+                "_links": {},
+                "_embedded": { "items": items },
+            }),
+        )
+    }
+
+    fn exists(&self, query: &[(String, String)]) -> Response {
+        let find = |url: &str| {
+            self.entries
+                .values()
+                .find(|e| e.url.as_deref() == Some(url))
+                .map(|e| e.id)
+        };
+
+        let urls: Vec<&str> = query
+            .iter()
+            .filter(|(k, _)| k.starts_with("urls"))
+            .map(|(_, v)| v.as_str())
+            .collect();
+        if !urls.is_empty() {
+            let result: HashMap<&str, Option<EntryId>> =
+                urls.into_iter().map(|url| (url, find(url))).collect();
+            return json_response(StatusCode::Ok, &result);
+        }
+
+        match query.iter().find(|(k, _)| k == "url") {
+            Some((_, url)) => json_response(StatusCode::Ok, &json!({ "exists": find(url) })),
+            None => not_found(),
+        }
+    }
+
+    /// Handle requests for a single entry.
+    fn entry(&mut self, method: Method, id: i64, body: &Value) -> Response {
+        match method {
+            Method::Get => json_response(StatusCode::Ok, &self.entries[&id]),
+            Method::Patch => {
+                self.patch_entry(id, body);
+                json_response(StatusCode::Ok, &self.entries[&id])
+            }
+            Method::Delete => {
+                let entry = self.entries.remove(&id).unwrap();
+                // the api doesn't include the id of deleted entries
+                let mut json = serde_json::to_value(&entry).unwrap();
+                if let Value::Object(ref mut map) = json {
+                    map.remove("id");
+                }
+                json_response(StatusCode::Ok, &json)
+            }
+            _ => not_found(),
+        }
+    }
+
+    /// Handle requests for sub resources of a single entry.
+    fn entry_action(&mut self, method: Method, id: i64, action: &str, body: &Value) -> Response {
+        match (method, action) {
+            (Method::Patch, "reload.json") => match self.reload_content.remove(&id) {
+                Some(content) => {
+                    let entry = self.entries.get_mut(&id).unwrap();
+                    entry.reading_time = reading_time(&content);
+                    entry.content = Some(content);
+                    entry.updated_at = Utc::now();
+                    json_response(StatusCode::Ok, &self.entries[&id])
+                }
+                None => Response::new(StatusCode::NotModified),
+            },
+            (Method::Get, "tags.json") => json_response(StatusCode::Ok, &self.entries[&id].tags),
+            (Method::Post, "tags.json") => {
+                let labels = get_tags(body, "tags").unwrap_or_default();
+                self.add_tags(id, &labels);
+                json_response(StatusCode::Ok, &self.entries[&id])
+            }
+            (Method::Get, export) => match export.strip_prefix("export.") {
+                Some(format) => self.export(id, format),
+                None => not_found(),
+            },
+            _ => not_found(),
+        }
+    }
+
+    /// Export an entry. Text based formats contain the entry data; binary formats are only a
+    /// recognizable placeholder.
+    fn export(&self, id: i64, format: &str) -> Response {
+        let entry = &self.entries[&id];
+        let title = entry.title.clone().unwrap_or_default();
+        let content = entry.content.clone().unwrap_or_default();
+        let tags = entry
+            .tags
+            .iter()
+            .map(|t| t.label.as_str())
+            .collect::<Vec<_>>();
+
+        let (mime, body): (mime::Mime, Body) = match format {
+            "json" => (mime::JSON, Body::from_json(&[entry]).unwrap()),
+            "txt" => (mime::PLAIN, Body::from_string(content)),
+            "csv" => (
+                mime::Mime::from("text/csv"),
+                Body::from_string(format!(
+                    "\"Title\",\"URL\",\"Content\",\"Tags\",\"MIME Type\",\"Language\",\"Creation date\"\n\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\n",
+                    title.replace('"', "\"\""),
+                    entry.url.clone().unwrap_or_default(),
+                    content.replace('"', "\"\""),
+                    tags.join(", "),
+                    entry.mimetype.clone().unwrap_or_default(),
+                    entry.language.clone().unwrap_or_default(),
+                    entry.created_at.format("%d/%m/%Y %H:%M:%S"),
+                )),
+            ),
+            "xml" => (
+                mime::XML,
+                Body::from_string(format!(
+                    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<result><entry><id>{}</id><title><![CDATA[{}]]></title><url><![CDATA[{}]]></url><content><![CDATA[{}]]></content></entry></result>\n",
+                    id,
+                    title,
+                    entry.url.clone().unwrap_or_default(),
+                    content,
+                )),
+            ),
+            "pdf" => (
+                mime::Mime::from("application/pdf"),
+                Body::from_bytes(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec()),
+            ),
+            "epub" => (
+                mime::Mime::from("application/epub+zip"),
+                Body::from_bytes(b"PK\x03\x04mimetypeapplication/epub+zip".to_vec()),
+            ),
+            "mobi" => (
+                mime::Mime::from("application/x-mobipocket-ebook"),
+                Body::from_bytes(b"\x00\x00\x00\x00BOOKMOBI".to_vec()),
+            ),
+            _ => return not_found(),
+        };
+
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body(body);
+        response.set_content_type(mime);
+        response
+    }
+
+    /// Handle annotation requests. Listing and creating use the entry id, updating and deleting
+    /// use the annotation id.
+    fn annotation(&mut self, method: Method, id: i64, body: &Value) -> Response {
+        match method {
+            Method::Get => match self.entries.get(&id) {
+                Some(entry) => {
+                    let rows = entry.annotations.as_deref().unwrap_or_default();
+                    json_response(
+                        StatusCode::Ok,
+                        &json!({ "total": rows.len(), "rows": rows }),
+                    )
+                }
+                None => not_found(),
+            },
+            Method::Post => {
+                if !self.entries.contains_key(&id) {
+                    return not_found();
+                }
+                let now = Utc::now();
+                let annotation = Annotation {
+                    id: AnnotationId(self.next_annotation_id),
+                    annotator_schema_version: "v1.0".to_owned(),
+                    created_at: now,
+                    quote: get_str(body, "quote"),
+                    ranges: get_ranges(body).unwrap_or_default(),
+                    text: get_str(body, "text").unwrap_or_default(),
+                    updated_at: now,
+                    user: None,
+                };
+                self.next_annotation_id += 1;
+                let response = json_response(StatusCode::Ok, &annotation);
+                let entry = self.entries.get_mut(&id).unwrap();
+                entry
+                    .annotations
+                    .get_or_insert_with(Vec::new)
+                    .push(annotation);
+                response
+            }
+            Method::Put | Method::Delete => {
+                for entry in self.entries.values_mut() {
+                    let annotations = entry.annotations.get_or_insert_with(Vec::new);
+                    if let Some(index) = annotations.iter().position(|a| *a.id == id) {
+                        if method == Method::Delete {
+                            let annotation = annotations.remove(index);
+                            return json_response(StatusCode::Ok, &annotation);
+                        }
+                        let annotation = &mut annotations[index];
+                        if let Some(text) = get_str(body, "text") {
+                            annotation.text = text;
+                        }
+                        if let Some(quote) = get_str(body, "quote") {
+                            annotation.quote = Some(quote);
+                        }
+                        if let Some(ranges) = get_ranges(body) {
+                            annotation.ranges = ranges;
+                        }
+                        annotation.updated_at = Utc::now();
+                        return json_response(StatusCode::Ok, annotation);
+                    }
+                }
+                not_found()
+            }
+            _ => not_found(),
+        }
+    }
+}
+
+fn get_ranges(data: &Value) -> Option<Vec<Range>> {
+    data.get("ranges")
+        .and_then(|ranges| serde_json::from_value(ranges.clone()).ok())
+}
+
+/// Estimate the reading time in minutes, assuming 200 words per minute.
+fn reading_time(content: &str) -> u32 {
+    (content.split_whitespace().count() / 200) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EntriesFilter, NewAnnotation, NewEntry, PatchEntry};
+    use crate::ClientError;
+    use async_std::task::block_on;
+
+    #[test]
+    fn test_invalid_credentials() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client_with_config(Config {
+                password: "wrong".to_owned(),
+                ..server.config()
+            });
+            assert!(client.get_tags().await.is_err());
+        })
+    }
+
+    #[test]
+    fn test_refresh_expired_token() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            client.get_tags().await.unwrap();
+
+            server.expire_tokens();
+            client.get_tags().await.unwrap();
+
+            assert_eq!(
+                server.requests(),
+                vec![
+                    "POST /oauth/v2/token",
+                    "GET /api/tags.json",
+                    "GET /api/tags.json",
+                    "POST /oauth/v2/token",
+                    "GET /api/tags.json",
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_entries_crud() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+
+            let mut new_entry = NewEntry::new_with_url("https://example.com/article".to_owned());
+            new_entry.tags = Some(vec!["rust".to_owned(), "async".to_owned()]);
+            let entry = client.create_entry(&new_entry).await.unwrap();
+            assert_eq!(entry.domain_name.as_deref(), Some("example.com"));
+            assert_eq!(entry.tags.len(), 2);
+
+            let patch = PatchEntry {
+                archive: Some(true),
+                ..Default::default()
+            };
+            let entry = client.update_entry(entry.id, &patch).await.unwrap();
+            assert!(entry.is_archived);
+
+            assert_eq!(
+                client
+                    .check_url_exists("https://example.com/article")
+                    .await
+                    .unwrap(),
+                Some(entry.id)
+            );
+
+            let deleted = client.delete_entry(entry.id).await.unwrap();
+            assert_eq!(deleted.id, entry.id);
+            assert!(matches!(
+                client.get_entry(entry.id).await,
+                Err(ClientError::NotFound { .. })
+            ));
+        })
+    }
+
+    #[test]
+    fn test_entries_pagination() {
+        block_on(async {
+            let server = MockServer::new();
+            for i in 0..5 {
+                server.add_entry(&format!("https://example.com/{}", i), &["paged"]);
+            }
+            server.add_entry("https://example.com/untagged", &[]);
+            let mut client = server.client();
+
+            let filter = EntriesFilter {
+                tags: vec!["paged".to_owned()],
+                per_page: Some(2),
+                ..Default::default()
+            };
+            let page = client.get_entries_page(&filter, 3).await.unwrap();
+            assert_eq!(page.total_pages, 3);
+            assert_eq!(page.total_entries, 5);
+            assert_eq!(page.entries.len(), 1);
+
+            assert_eq!(
+                client.get_entries_with_filter(&filter).await.unwrap().len(),
+                5
+            );
+            assert!(client.get_entries_page(&filter, 4).await.is_err());
+        })
+    }
+
+    #[test]
+    fn test_tags_and_annotations() {
+        block_on(async {
+            let server = MockServer::new();
+            let entry = server.add_entry("https://example.com/", &["one", "two"]);
+            let mut client = server.client();
+
+            let tags = client.get_tags().await.unwrap();
+            assert_eq!(tags.len(), 2);
+            let entry = client
+                .delete_tag_from_entry(entry.id, tags[0].id)
+                .await
+                .unwrap();
+            assert_eq!(entry.tags.len(), 1);
+            client.delete_tag_by_label("two").await.unwrap();
+            assert!(client
+                .get_tags_for_entry(entry.id)
+                .await
+                .unwrap()
+                .is_empty());
+
+            let new_annotation = NewAnnotation {
+                quote: "Content".to_owned(),
+                ranges: vec![Range {
+                    start: Some("/p[1]".to_owned()),
+                    end: Some("/p[1]".to_owned()),
+                    start_offset: 0,
+                    end_offset: 7,
+                }],
+                text: "note".to_owned(),
+            };
+            let mut annotation = client
+                .create_annotation(entry.id, &new_annotation)
+                .await
+                .unwrap();
+            annotation.text = "updated note".to_owned();
+            client.update_annotation(&annotation).await.unwrap();
+            let annotations = client.get_annotations(entry.id).await.unwrap();
+            assert_eq!(annotations.len(), 1);
+            assert_eq!(annotations[0].text, "updated note");
+        })
+    }
+
+    #[test]
+    fn test_export_and_reload() {
+        block_on(async {
+            let server = MockServer::new();
+            let entry = server.add_entry("https://example.com/", &[]);
+            let mut client = server.client();
+
+            let text = client
+                .export_entry(entry.id, crate::types::Format::TXT)
+                .await
+                .unwrap();
+            assert_eq!(text, "<p>Content fetched from https://example.com/</p>");
+
+            assert!(matches!(
+                client.reload_entry(entry.id).await,
+                Err(ClientError::NotModified { .. })
+            ));
+            server.set_reload_content(entry.id, "<p>New content</p>");
+            let entry = client.reload_entry(entry.id).await.unwrap();
+            assert_eq!(entry.content.as_deref(), Some("<p>New content</p>"));
+        })
+    }
+}
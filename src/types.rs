@@ -8,10 +8,8 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-// TODO: decide how to implement PartialEq/Eq for types here. (eg on the whole
-// structs, or just by IDs?)
-
 mod annotations;
+mod authors;
 mod common;
 mod entries_filter;
 mod entry;
@@ -25,27 +23,28 @@ mod user;
 // re-export submodule types
 pub(crate) use self::annotations::AnnotationRows;
 pub use self::annotations::{Annotation, Annotations, NewAnnotation, Range};
-pub use self::common::ID;
+pub use self::authors::Authors;
+pub use self::common::{AnnotationId, EntryId, TagId, ID};
 pub(crate) use self::entries_filter::RequestEntriesFilter;
 pub use self::entries_filter::{EntriesFilter, SortBy, SortOrder};
 pub(crate) use self::entry::{DeletedEntry, PaginatedEntries};
 pub use self::entry::{Entries, EntriesPage, Entry};
 pub use self::format::Format;
 pub use self::new_entry::NewEntry;
-pub use self::patch_entry::PatchEntry;
+pub use self::patch_entry::{EntryDiff, EntryField, PatchEntry};
 pub(crate) use self::query::EntriesExistParams;
 pub use self::tags::{DeletedTag, Tag, TagString, Tags};
 pub use self::user::{NewlyRegisteredInfo, RegisterInfo, User};
 
 /// used internally to store information about the oauth token
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub(crate) struct TokenInfo {
     pub access_token: String,
     pub refresh_token: String,
 }
 
 /// configuration to use to init a `Client`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     /// An oauth client id.
     pub client_id: String,
@@ -66,13 +65,13 @@ pub struct Config {
 
 /// The type returned from `check_exists`. The format is URL: ID. If ID is None,
 /// then that url doesn't exist in the db.
-pub type ExistsInfo = HashMap<String, Option<ID>>;
+pub type ExistsInfo = HashMap<String, Option<EntryId>>;
 
 /// Internal struct for deserializing a response upon checking the existence of
 /// a url.
 #[derive(Deserialize, Debug)]
 pub(crate) struct ExistsResponse {
-    pub exists: Option<ID>,
+    pub exists: Option<EntryId>,
 }
 
 /// a little trick to get around having to provide type annotations for a unit or
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use chrono::{DateTime, Utc};
 
-use super::common::ID;
+use super::common::AnnotationId;
 use crate::utils::serde::parse_stringint;
 
 /// Type alias for clarity.
@@ -17,10 +17,10 @@ pub type Annotations = Vec<Annotation>;
 /// See <http://docs.annotatorjs.org/en/v1.2.x/annotation-format.html> for documentation on the
 /// format.
 ///
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Annotation {
     /// The unique integral id of the annotation.
-    pub id: ID,
+    pub id: AnnotationId,
 
     /// A schema version to presumably support updates in the future. Currently all annotations
     /// appear to be `v1.0`. Hopefully this isn't going to get breaking changes any time soon.
@@ -48,17 +48,17 @@ pub struct Annotation {
     pub user: Option<String>,
 }
 
-/// This is implemented so that an Annotation can be used interchangeably with an ID for some
-/// client methods. For convenience.
-impl From<Annotation> for ID {
+/// This is implemented so that an Annotation can be used interchangeably with an AnnotationId
+/// for some client methods. For convenience.
+impl From<Annotation> for AnnotationId {
     fn from(ann: Annotation) -> Self {
         ann.id
     }
 }
 
-/// This is implemented so that an &Annotation can be used interchangeably with an ID
-/// for some client methods. For convenience.
-impl From<&Annotation> for ID {
+/// This is implemented so that an &Annotation can be used interchangeably with an
+/// AnnotationId for some client methods. For convenience.
+impl From<&Annotation> for AnnotationId {
     fn from(ann: &Annotation) -> Self {
         ann.id
     }
@@ -72,9 +72,9 @@ pub(crate) struct AnnotationRows {
 
 /// Represents an annotation to be created (hence no ID yet).
 /// Fields are defined as in a full annotation.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NewAnnotation {
-    /// TODO, XXX: quote must not be an empty string.
+    /// Must not be empty. This is checked by validation before the annotation is sent.
     pub quote: String,
     pub ranges: Vec<Range>,
     pub text: String,
@@ -83,7 +83,7 @@ pub struct NewAnnotation {
 /// Range as used in an `Annotation`. Shows where the annotation is in the
 /// content. Part of Annotationjs annotation format. I quote from their docs for the field
 /// descriptions.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct Range {
     /// (relative) XPath to start element.
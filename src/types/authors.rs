@@ -0,0 +1,118 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The authors of an entry.
+///
+/// The server returns authors as a list (`Entry::published_by`), but expects them as a comma
+/// separated string when creating or updating an entry. This type is used for both, so authors
+/// read from an entry can be sent back unchanged. Names containing a comma can't be sent, and
+/// are rejected by validation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Authors(Vec<String>);
+
+impl Authors {
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Authors(
+            names
+                .into_iter()
+                .map(|name| name.into().trim().to_owned())
+                .filter(|name| !name.is_empty())
+                .collect(),
+        )
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The authors in the format expected by the server: "name 1,name 2".
+    pub fn to_api_string(&self) -> String {
+        self.0.join(",")
+    }
+}
+
+/// Parse a comma separated list of names.
+impl FromStr for Authors {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Authors::new(s.split(',')))
+    }
+}
+
+impl From<&str> for Authors {
+    fn from(s: &str) -> Self {
+        Authors::new(s.split(','))
+    }
+}
+
+impl From<Vec<String>> for Authors {
+    fn from(names: Vec<String>) -> Self {
+        Authors::new(names)
+    }
+}
+
+/// Formats as "name 1, name 2".
+impl fmt::Display for Authors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.join(", "))
+    }
+}
+
+/// Serializes to the comma separated format expected by the server.
+impl Serialize for Authors {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_api_string())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AuthorsRepr {
+    /// As returned by the server. Null names have been observed.
+    List(Vec<Option<String>>),
+    Joined(String),
+}
+
+/// Accepts both the list returned by the server and the comma separated format.
+impl<'de> Deserialize<'de> for Authors {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match AuthorsRepr::deserialize(deserializer)? {
+            AuthorsRepr::List(names) => Authors::new(names.into_iter().flatten()),
+            AuthorsRepr::Joined(joined) => Authors::from(joined.as_str()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authors_formats() {
+        let read: Authors =
+            serde_json::from_str(r#"["Ada Lovelace", null, "Grace Hopper"]"#).unwrap();
+        assert_eq!(read.names(), ["Ada Lovelace", "Grace Hopper"]);
+        assert_eq!(
+            serde_json::to_string(&read).unwrap(),
+            r#""Ada Lovelace,Grace Hopper""#
+        );
+        assert_eq!(read.to_string(), "Ada Lovelace, Grace Hopper");
+
+        let written: Authors = serde_json::from_str(r#""Ada Lovelace, Grace Hopper""#).unwrap();
+        assert_eq!(written, read);
+    }
+}
@@ -6,7 +6,8 @@ use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
 
-/// The type used as an ID for all data structures. Declared for clarity.
+/// The type used as an ID for data structures that don't have their own id type, eg. users.
+/// Entries, tags and annotations use `EntryId`, `TagId` and `AnnotationId` instead.
 #[derive(Serialize, Deserialize, Hash, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ID(pub i64);
 
@@ -47,6 +48,84 @@ impl Deref for ID {
     }
 }
 
+/// Declares an id type for a single kind of resource. These have the same conveniences as `ID`,
+/// but can't be mixed up with each other.
+macro_rules! resource_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Hash, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        pub struct $name(pub i64);
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl $name {
+            pub fn as_int(self) -> i64 {
+                self.0
+            }
+        }
+
+        /// For convenience.
+        impl From<i32> for $name {
+            fn from(x: i32) -> Self {
+                $name(i64::from(x))
+            }
+        }
+
+        /// For convenience.
+        impl From<i64> for $name {
+            fn from(x: i64) -> Self {
+                $name(x)
+            }
+        }
+
+        /// Migration path for code that still uses the generic `ID`.
+        impl From<ID> for $name {
+            fn from(id: ID) -> Self {
+                $name(id.0)
+            }
+        }
+
+        impl From<$name> for ID {
+            fn from(id: $name) -> Self {
+                ID(id.0)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = i64;
+
+            fn deref(&self) -> &i64 {
+                &self.0
+            }
+        }
+    };
+}
+
+resource_id!(
+    /// The id of an `Entry`. Client methods for other resources don't accept it:
+    ///
+    /// ```compile_fail
+    /// # use wallabag_api::types::{EntryId, TagId};
+    /// fn delete_tag<T: Into<TagId>>(_id: T) {}
+    /// delete_tag(EntryId(1));
+    /// ```
+    EntryId
+);
+
+resource_id!(
+    /// The id of a `Tag`.
+    TagId
+);
+
+resource_id!(
+    /// The id of an `Annotation`.
+    AnnotationId
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,4 +139,13 @@ mod tests {
         // test that can be passed to a function as a reference to an i64
         foo(&ID(234));
     }
+
+    #[test]
+    fn test_resource_ids() {
+        assert_eq!(EntryId::from(ID(3)), EntryId(3));
+        assert_eq!(ID::from(TagId(4)), ID(4));
+        assert_eq!(*AnnotationId(5), 5);
+        assert_eq!(serde_json::to_string(&EntryId(6)).unwrap(), "6");
+        assert_eq!(serde_json::from_str::<TagId>("7").unwrap(), TagId(7));
+    }
 }
@@ -6,7 +6,7 @@ use serde::Serializer;
 use std::result::Result;
 
 /// Used in `EntriesFilter` for ordering results.
-#[derive(Serialize, Debug, Clone, Copy)]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum SortOrder {
     Asc,
@@ -14,7 +14,7 @@ pub enum SortOrder {
 }
 
 /// Used in `EntriesFilter` for sorting results.
-#[derive(Serialize, Debug, Clone, Copy)]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum SortBy {
     Created,
@@ -23,7 +23,7 @@ pub enum SortBy {
 
 /// Represents possible filters to apply to `get_entries_filtered`. To use the
 /// default for a filter, set the value to `None`.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EntriesFilter {
     /// None = all entries; true/false filters by archived or not archived only
     pub archive: Option<bool>,
@@ -4,12 +4,14 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
-use crate::utils::serde::parse_hashmap_with_null_values;
 use crate::utils::serde::parse_intbool;
+use crate::utils::serde::{authors_as_list, parse_hashmap_with_null_values};
 
 use super::annotations::Annotations;
-use super::common::ID;
+use super::authors::Authors;
+use super::common::{EntryId, ID};
 use super::tags::Tags;
 
 /// type alias: a list of entries as returned from some endpoints
@@ -22,7 +24,10 @@ pub type Entries = Vec<Entry>;
 /// Most fields are controlled by the server. When creating an entry, the server will send a
 /// request to the given url and use the response to populate many of the fields. This response is
 /// what `headers`, `http_status`, `mimetype`, etc. are referring to.
-#[derive(Deserialize, Serialize, Debug)]
+/// Entries compare equal only if all fields are equal, but are hashed by id alone. There is no
+/// `Ord`, since ordering by id would consider two versions of the same entry equal; sort with
+/// `entries.sort_by_key(|entry| entry.id)` instead.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Entry {
     /// Annotation objects for this entry.
     pub annotations: Option<Annotations>,
@@ -46,7 +51,7 @@ pub struct Entry {
 
     /// ID of the entry. Should be an integer. Should also be unique, so can use this directly as
     /// the local id if storing the entry in a DB.
-    pub id: ID,
+    pub id: EntryId,
 
     /// The archived (or read) status of the entry. These boolean options are sometimes represented
     /// as 0 or 1 from the API, which makes parsing in a strongly typed language annoying.
@@ -84,8 +89,10 @@ pub struct Entry {
     /// Data about when the entry was published (scraped from the original web page).
     pub published_at: Option<DateTime<Utc>>,
 
-    /// Data about who published the entry (scraped from the original web page).
-    pub published_by: Option<Vec<Option<String>>>,
+    /// Data about who published the entry (scraped from the original web page). Can be passed
+    /// as `authors` when creating or updating an entry.
+    #[serde(serialize_with = "authors_as_list")]
+    pub published_by: Option<Authors>,
 
     /// Estimated reading time in minutes. Generated by the server, probably based off your set
     /// reading speed or a default.
@@ -128,6 +135,18 @@ pub struct Entry {
     pub user_name: String,
 }
 
+impl Entry {
+    /// A title to show for the entry: its title, or its url if the title is blank, or
+    /// "Entry {id}" if it has neither.
+    pub fn display_title(&self) -> String {
+        self.title
+            .clone()
+            .filter(|title| !title.trim().is_empty())
+            .or_else(|| self.url.clone())
+            .unwrap_or_else(|| format!("Entry {}", self.id))
+    }
+}
+
 /// A struct representing a deleted entry from wallabag (a full saved article including
 /// annotations and tags). The only difference from the full entry is that this
 /// doesn't have an id. Only used internally because a full entry gets
@@ -154,7 +173,7 @@ pub(crate) struct DeletedEntry {
     pub origin_url: Option<String>,
     pub preview_picture: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
-    pub published_by: Option<Vec<Option<String>>>,
+    pub published_by: Option<Authors>,
     pub reading_time: u32,
     pub starred_at: Option<DateTime<Utc>>,
     pub tags: Tags,
@@ -167,17 +186,23 @@ pub(crate) struct DeletedEntry {
     pub user_name: String,
 }
 
-/// This is implemented so that an Entry can be used interchangeably with an ID
+impl Hash for Entry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// This is implemented so that an Entry can be used interchangeably with an EntryId
 /// for some client methods. For convenience.
-impl From<Entry> for ID {
+impl From<Entry> for EntryId {
     fn from(entry: Entry) -> Self {
         entry.id
     }
 }
 
-/// This is implemented so that an &Entry can be used interchangeably with an ID
+/// This is implemented so that an &Entry can be used interchangeably with an EntryId
 /// for some client methods. For convenience.
-impl From<&Entry> for ID {
+impl From<&Entry> for EntryId {
     fn from(entry: &Entry) -> Self {
         entry.id
     }
@@ -202,7 +227,7 @@ pub(crate) struct EmbeddedEntries {
 }
 
 /// Represents a page of Entries returned. Includes both the payload and metadata about the page.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EntriesPage {
     /// Number of entries returned per page. This is set by the server; useful to know if you're
     /// accepting the server default because this will inform what the server default is.
@@ -319,4 +344,48 @@ mod tests {
         let entry: Entry = serde_json::from_str(text).unwrap();
         assert_eq!(entry.headers, Some(HashMap::from([])));
     }
+
+    #[test]
+    fn test_entry_identity() {
+        use crate::testing::MockServer;
+        use std::collections::HashSet;
+
+        let server = MockServer::new();
+        let entry = server.add_entry("https://example.com/a", &["rust"]);
+        let mut changed = entry.clone();
+        changed.is_starred = !changed.is_starred;
+
+        assert_eq!(entry, entry.clone());
+        assert_ne!(entry, changed);
+        let set: HashSet<Entry> = vec![entry.clone(), changed.clone(), entry]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_display_title() {
+        use crate::testing::MockServer;
+
+        let server = MockServer::new();
+        let entry = server.add_entry("https://example.com/a", &[]);
+        let titled = Entry {
+            title: Some("Title".to_owned()),
+            ..entry.clone()
+        };
+        assert_eq!(titled.display_title(), "Title");
+
+        let blank = Entry {
+            title: Some(" ".to_owned()),
+            ..entry.clone()
+        };
+        assert_eq!(blank.display_title(), "https://example.com/a");
+
+        let neither = Entry {
+            title: None,
+            url: None,
+            ..entry.clone()
+        };
+        assert_eq!(neither.display_title(), format!("Entry {}", entry.id));
+    }
 }
@@ -4,7 +4,7 @@
 use std::fmt;
 
 /// Use to represent a format to export to.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Format {
     XML,
     JSON,
@@ -35,6 +35,25 @@ impl fmt::Display for Format {
 }
 
 impl Format {
+    /// The content types the server uses for exports in this format.
+    pub fn content_types(self) -> &'static [&'static str] {
+        use self::Format::*;
+        match self {
+            XML => &["application/xml", "text/xml"],
+            JSON => &["application/json"],
+            TXT => &["text/plain"],
+            CSV => &["application/csv", "text/csv"],
+            PDF => &["application/pdf"],
+            EPUB => &["application/epub+zip"],
+            MOBI => &["application/x-mobipocket-ebook"],
+        }
+    }
+
+    /// Whether exports in this format are binary files rather than text.
+    pub fn is_binary(self) -> bool {
+        matches!(self, Format::PDF | Format::EPUB | Format::MOBI)
+    }
+
     /// Create a `Format` from a str. Case-insensitive. Returns `None` if could
     /// not determine format.
     ///
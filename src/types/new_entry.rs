@@ -5,19 +5,20 @@ use chrono::{DateTime, Utc};
 
 use serde::{Deserialize, Serialize};
 
+use super::Authors;
 use crate::utils::serde::bool_to_int;
 
 /// A struct representing an entry to be created.
 /// At least `url` must be provided. If you wish to provide the HTML content you
 /// must also provide `content` and `title` to prevent the wallabag server from
 /// fetching it from the url.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct NewEntry {
     pub url: String,
     pub title: Option<String>,
 
-    /// Tags containing a comma are valid but discouraged...
-    /// Also note that these are tag labels as strings, not Tag objects.
+    /// Tag labels as strings, not Tag objects. Labels containing a comma are rejected by
+    /// validation, since the server would split them into multiple tags.
     pub tags: Option<Vec<String>>,
 
     #[serde(serialize_with = "bool_to_int")]
@@ -32,8 +33,7 @@ pub struct NewEntry {
     pub preview_picture: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
 
-    /// Formatted as "name 1, name 2"
-    pub authors: Option<String>,
+    pub authors: Option<Authors>,
 
     pub origin_url: Option<String>,
 }
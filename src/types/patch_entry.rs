@@ -5,18 +5,21 @@ use chrono::{DateTime, Utc};
 
 use serde::{Deserialize, Serialize};
 
-use super::Entry;
+use super::{Authors, Entry, Tag};
 use crate::utils::serde::bool_to_int;
 
 /// A struct representing an entry to be changed. Fields here are the only fields that can be
 /// modified directly via the api.
 ///
-/// Setting a field to `None` causes the field to not be modified.
-#[derive(Deserialize, Serialize, Debug)]
+/// Setting a field to `None` causes the field to not be modified. The server ignores empty
+/// values, so a field can be replaced but not removed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PatchEntry {
     pub title: Option<String>,
 
-    /// List of tag labels as strings. Commas in tag labels are valid but discouraged.
+    /// List of tag labels as strings, which replaces the tags of the entry. The server ignores an
+    /// empty list, so tags can't all be removed this way. Labels containing a comma are rejected
+    /// by validation.
     pub tags: Option<Vec<String>>,
 
     #[serde(serialize_with = "bool_to_int")]
@@ -31,8 +34,7 @@ pub struct PatchEntry {
     pub preview_picture: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
 
-    /// Formatted as "name 1, name 2"
-    pub authors: Option<String>,
+    pub authors: Option<Authors>,
 
     pub origin_url: Option<String>,
 }
@@ -61,7 +63,8 @@ impl Default for PatchEntry {
     }
 }
 
-/// Convert an Entry to a set of changes ready for sending to the api.
+/// Convert an Entry to a set of changes ready for sending to the api. Authors are left out if a
+/// name contains a comma, since the server splits authors on commas.
 impl From<&Entry> for PatchEntry {
     fn from(entry: &Entry) -> Self {
         let tags: Vec<String> = entry.tags.iter().map(|t| t.label.clone()).collect();
@@ -75,8 +78,373 @@ impl From<&Entry> for PatchEntry {
             language: entry.language.clone(),
             preview_picture: entry.preview_picture.clone(),
             published_at: entry.published_at,
-            authors: None,
+            authors: sendable_authors(&entry.published_by),
             origin_url: entry.origin_url.clone(),
         }
     }
 }
+
+/// The authors to send for an entry. The server splits authors on commas, so authors with a
+/// comma in a name can't be sent without changing them, and are left out.
+fn sendable_authors(authors: &Option<Authors>) -> Option<Authors> {
+    authors
+        .clone()
+        .filter(|authors| authors.names().iter().all(|name| !name.contains(',')))
+}
+
+/// A field of an entry that can be changed with a `PatchEntry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryField {
+    Title,
+    Tags,
+    Archive,
+    Starred,
+    Public,
+    Content,
+    Language,
+    PreviewPicture,
+    PublishedAt,
+    Authors,
+    OriginUrl,
+}
+
+/// The changes between two versions of an entry. See `PatchEntry::diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryDiff {
+    /// The changes to send with `Client::update_entry`. Only changed fields are set.
+    pub patch: PatchEntry,
+
+    /// The fields that changed, in declaration order of `EntryField`.
+    pub changed: Vec<EntryField>,
+
+    /// Changed fields that are left out of the patch, because the server can't store the new
+    /// value: fields that were removed in the new entry, and authors with a comma in a name.
+    pub cleared: Vec<EntryField>,
+
+    /// Tags that were removed. Applying the patch removes them, except when all tags were
+    /// removed: the server ignores an empty list of tags, so then they need to be removed with
+    /// `Client::delete_tag_from_entry`.
+    pub removed_tags: Vec<Tag>,
+}
+
+impl EntryDiff {
+    /// True if the entries are equal in all fields that can be patched.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
+}
+
+impl PatchEntry {
+    /// Compute the minimal changes to turn `old` into `new`. Tags are compared as a set of
+    /// labels, so reordering them is not a change. If they changed, `patch.tags` contains all
+    /// labels of `new`, because a patch replaces the tags of the entry.
+    /// A field that was set in `old` but is `None` in `new` can't be patched; it is listed in
+    /// `cleared`, like authors with a comma in a name.
+    pub fn diff(old: &Entry, new: &Entry) -> EntryDiff {
+        let mut patch = PatchEntry::default();
+        let mut changed = Vec::new();
+        let mut cleared = Vec::new();
+
+        fn field<T: Clone + PartialEq>(
+            old: &Option<T>,
+            new: &Option<T>,
+            name: EntryField,
+            target: &mut Option<T>,
+            changed: &mut Vec<EntryField>,
+            cleared: &mut Vec<EntryField>,
+        ) {
+            if old != new {
+                changed.push(name);
+                match new {
+                    Some(_) => *target = new.clone(),
+                    None => cleared.push(name),
+                }
+            }
+        }
+
+        fn flag(
+            old: bool,
+            new: bool,
+            name: EntryField,
+            target: &mut Option<bool>,
+            changed: &mut Vec<EntryField>,
+        ) {
+            if old != new {
+                changed.push(name);
+                *target = Some(new);
+            }
+        }
+
+        field(
+            &old.title,
+            &new.title,
+            EntryField::Title,
+            &mut patch.title,
+            &mut changed,
+            &mut cleared,
+        );
+
+        let added_tags = new
+            .tags
+            .iter()
+            .any(|tag| !old.tags.iter().any(|t| t.label == tag.label));
+        let removed_tags: Vec<Tag> = old
+            .tags
+            .iter()
+            .filter(|tag| !new.tags.iter().any(|t| t.label == tag.label))
+            .cloned()
+            .collect();
+        if added_tags || !removed_tags.is_empty() {
+            changed.push(EntryField::Tags);
+            if !new.tags.is_empty() {
+                patch.tags = Some(new.tags.iter().map(|tag| tag.label.clone()).collect());
+            }
+        }
+
+        flag(
+            old.is_archived,
+            new.is_archived,
+            EntryField::Archive,
+            &mut patch.archive,
+            &mut changed,
+        );
+        flag(
+            old.is_starred,
+            new.is_starred,
+            EntryField::Starred,
+            &mut patch.starred,
+            &mut changed,
+        );
+        flag(
+            old.is_public,
+            new.is_public,
+            EntryField::Public,
+            &mut patch.public,
+            &mut changed,
+        );
+        field(
+            &old.content,
+            &new.content,
+            EntryField::Content,
+            &mut patch.content,
+            &mut changed,
+            &mut cleared,
+        );
+        field(
+            &old.language,
+            &new.language,
+            EntryField::Language,
+            &mut patch.language,
+            &mut changed,
+            &mut cleared,
+        );
+        field(
+            &old.preview_picture,
+            &new.preview_picture,
+            EntryField::PreviewPicture,
+            &mut patch.preview_picture,
+            &mut changed,
+            &mut cleared,
+        );
+        field(
+            &old.published_at,
+            &new.published_at,
+            EntryField::PublishedAt,
+            &mut patch.published_at,
+            &mut changed,
+            &mut cleared,
+        );
+        if old.published_by != new.published_by {
+            changed.push(EntryField::Authors);
+            patch.authors = sendable_authors(&new.published_by);
+            if patch.authors.is_none() {
+                cleared.push(EntryField::Authors);
+            }
+        }
+        field(
+            &old.origin_url,
+            &new.origin_url,
+            EntryField::OriginUrl,
+            &mut patch.origin_url,
+            &mut changed,
+            &mut cleared,
+        );
+
+        EntryDiff {
+            patch,
+            changed,
+            cleared,
+            removed_tags,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use async_std::task::block_on;
+
+    #[test]
+    fn test_diff_only_changed_fields() {
+        let server = MockServer::new();
+        let old = server.add_entry("https://example.com/a", &["rust", "async"]);
+
+        let diff = PatchEntry::diff(&old, &old.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.patch, PatchEntry::default());
+
+        let mut new = old.clone();
+        new.is_starred = !old.is_starred;
+        new.title = Some("New title".to_owned());
+        new.tags.reverse();
+        let diff = PatchEntry::diff(&old, &new);
+        assert_eq!(diff.changed, vec![EntryField::Title, EntryField::Starred]);
+        assert_eq!(
+            diff.patch,
+            PatchEntry {
+                title: Some("New title".to_owned()),
+                starred: Some(!old.is_starred),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_tags_as_set() {
+        let server = MockServer::new();
+        let old = server.add_entry("https://example.com/a", &["rust", "async"]);
+        let other = server.add_entry("https://example.com/b", &["async", "web"]);
+
+        let mut new = old.clone();
+        new.tags = other.tags;
+        let diff = PatchEntry::diff(&old, &new);
+        assert_eq!(diff.changed, vec![EntryField::Tags]);
+        assert_eq!(
+            diff.patch.tags,
+            Some(vec!["async".to_owned(), "web".to_owned()])
+        );
+        assert_eq!(
+            diff.removed_tags
+                .iter()
+                .map(|t| t.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["rust"]
+        );
+    }
+
+    #[test]
+    fn test_apply_tags_diff() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let old = server.add_entry("https://example.com/a", &["rust", "async"]);
+
+            let mut new = old.clone();
+            new.tags.retain(|tag| tag.label == "async");
+            let diff = PatchEntry::diff(&old, &new);
+            let entry = client.update_entry(old.id, &diff.patch).await.unwrap();
+            assert_eq!(
+                entry
+                    .tags
+                    .iter()
+                    .map(|t| t.label.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["async"]
+            );
+
+            // An empty list of tags is ignored by the server, so the patch leaves tags alone.
+            let diff = PatchEntry::diff(
+                &entry,
+                &Entry {
+                    tags: vec![],
+                    ..entry.clone()
+                },
+            );
+            assert_eq!(diff.patch.tags, None);
+            assert_eq!(diff.removed_tags, entry.tags);
+        })
+    }
+
+    #[test]
+    fn test_authors_round_trip() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+            let mut new_entry =
+                crate::types::NewEntry::new_with_url("https://example.com/a".into());
+            new_entry.authors = Some(Authors::new(vec!["Ada Lovelace", "Grace Hopper"]));
+            let entry = client.create_entry(&new_entry).await.unwrap();
+
+            let patch = PatchEntry::from(&entry);
+            assert_eq!(patch.authors, new_entry.authors);
+            let updated = client.update_entry(entry.id, &patch).await.unwrap();
+            assert_eq!(updated.published_by, new_entry.authors);
+
+            // A name with a comma can't be sent, so both conversions leave the authors alone.
+            let renamed = Entry {
+                published_by: Some(Authors::new(vec!["Lovelace, Ada"])),
+                ..updated.clone()
+            };
+            let patch = PatchEntry::from(&renamed);
+            assert_eq!(patch.authors, None);
+            let diff = PatchEntry::diff(&updated, &renamed);
+            assert_eq!(diff.changed, vec![EntryField::Authors]);
+            assert_eq!(diff.cleared, vec![EntryField::Authors]);
+            assert_eq!(diff.patch.authors, None);
+
+            let updated = client.update_entry(entry.id, &patch).await.unwrap();
+            assert_eq!(updated.published_by, new_entry.authors);
+        })
+    }
+
+    #[test]
+    fn test_patch_serialization() {
+        let patch = PatchEntry {
+            archive: Some(true),
+            language: Some("en".to_owned()),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&patch).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "title": null,
+                "tags": null,
+                "archive": 1,
+                "starred": null,
+                "public": null,
+                "content": null,
+                "language": "en",
+                "preview_picture": null,
+                "published_at": null,
+                "authors": null,
+                "origin_url": null,
+            })
+        );
+
+        let parsed: PatchEntry =
+            serde_json::from_str(r#"{"language": "en", "authors": null}"#).unwrap();
+        assert_eq!(parsed.language, Some("en".to_owned()));
+        assert_eq!(parsed.authors, None);
+        assert_eq!(parsed.title, None);
+    }
+
+    #[test]
+    fn test_diff_cleared_fields() {
+        let server = MockServer::new();
+        let old = Entry {
+            origin_url: Some("https://example.com/origin".to_owned()),
+            ..server.add_entry("https://example.com/a", &[])
+        };
+
+        let new = Entry {
+            origin_url: None,
+            ..old.clone()
+        };
+        let diff = PatchEntry::diff(&old, &new);
+        assert_eq!(diff.changed, vec![EntryField::OriginUrl]);
+        assert_eq!(diff.cleared, vec![EntryField::OriginUrl]);
+        assert_eq!(diff.patch, PatchEntry::default());
+    }
+}
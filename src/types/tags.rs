@@ -1,21 +1,22 @@
 // Copyright 2018 Samuel Walladge <samuel@swalladge.net>
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::cmp::Ordering;
 use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
-use super::common::ID;
+use super::common::TagId;
 use crate::errors::TagStringError;
 
 /// List of tags declared for clarity.
 pub type Tags = Vec<Tag>;
 
-/// Represents a tag from the API.
-#[derive(Deserialize, Serialize, Debug)]
+/// Represents a tag from the API. Tags are ordered by slug.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Tag {
     /// The unique tag ID.
-    pub id: ID,
+    pub id: TagId,
 
     /// The label aka name. The API accepts tags with commas in the label, but this is discouraged
     /// as some API methods require tags to be supplied as a comma separated string (eg.
@@ -27,15 +28,35 @@ pub struct Tag {
     pub slug: String,
 }
 
-/// Convenience method to use an ID or Tag interchangeably in client methods.
-impl From<Tag> for ID {
+impl Ord for Tag {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the id and label only break ties, to stay consistent with `Eq`
+        (&self.slug, self.id, &self.label).cmp(&(&other.slug, other.id, &other.label))
+    }
+}
+
+impl PartialOrd for Tag {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Convenience method to use a TagId or Tag interchangeably in client methods.
+impl From<Tag> for TagId {
     fn from(tag: Tag) -> Self {
         tag.id
     }
 }
 
+/// Convenience method to use a TagId or &Tag interchangeably in client methods.
+impl From<&Tag> for TagId {
+    fn from(tag: &Tag) -> Self {
+        tag.id
+    }
+}
+
 /// Represents a deleted tag, since deleted tags don't come with IDs.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DeletedTag {
     pub label: String,
     pub slug: String,
@@ -44,7 +65,7 @@ pub struct DeletedTag {
 /// Represents a valid tag name for tags when sent to the API as a comma separated string. (eg.
 /// "tag1,tag2") Only client methods that need to format a list of tags in this way will use
 /// this.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct TagString {
     label: String,
 }
@@ -78,3 +99,23 @@ impl TagString {
         self.label
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_sort_by_slug() {
+        let tag = |id, slug: &str| Tag {
+            id: TagId(id),
+            label: slug.to_owned(),
+            slug: slug.to_owned(),
+        };
+        let mut tags = [tag(1, "zebra"), tag(2, "apple"), tag(3, "mango")];
+        tags.sort();
+        assert_eq!(
+            tags.iter().map(|t| t.slug.as_str()).collect::<Vec<_>>(),
+            vec!["apple", "mango", "zebra"]
+        );
+    }
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::types::ID;
 
 /// A struct representing a user. (ie. you) Fields should be self-explanatory.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct User {
     pub id: ID,
     pub username: String,
@@ -18,7 +18,7 @@ pub struct User {
 
 /// A struct representing a user to be registered. Includes the name for the client to be
 /// registered along with.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterInfo {
     pub username: String,
     pub password: String,
@@ -27,7 +27,7 @@ pub struct RegisterInfo {
 }
 
 /// A struct representing a newly created user and associated client info.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct NewlyRegisteredInfo {
     pub id: ID,
     pub username: String,
@@ -38,7 +38,7 @@ pub struct NewlyRegisteredInfo {
 }
 
 /// Represents the oauth client details.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct ClientInfo {
     client_id: String,
     client_secret: String,
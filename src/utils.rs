@@ -1,14 +1,17 @@
 // Copyright 2018 Samuel Walladge <samuel@swalladge.net>
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+pub(crate) mod redact;
 pub(crate) mod serde;
+pub(crate) mod telemetry;
+pub(crate) mod xml;
 
 use std::fmt;
 
-use crate::types::{Format, ID};
+use crate::types::{AnnotationId, EntryId, Format, TagId};
 
 /// Used for building API endpoint urls from the client.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum EndPoint {
     Token,
     Entries,
@@ -18,13 +21,19 @@ pub(crate) enum EndPoint {
     Tags,
     TagsLabel,
     TagLabel,
-    DeleteEntryTag(ID, ID), // entry id, tag id
-    EntryReload(ID),
-    EntryTags(ID), // entry id
-    Export(ID, Format),
-    Tag(ID),
-    Entry(ID),
-    Annotation(ID),
+    DeleteEntryTag(EntryId, TagId),
+    EntryReload(EntryId),
+    EntryTags(EntryId),
+    Export(EntryId, Format),
+    /// Export of a category of entries, eg. `unread`. This is a route of the web interface, not
+    /// of the API.
+    CategoryExport(&'static str, Format),
+    Tag(TagId),
+    Entry(EntryId),
+    /// Listing and creating annotations of an entry.
+    Annotations(EntryId),
+    /// Updating and deleting a single annotation.
+    Annotation(AnnotationId),
 }
 
 impl fmt::Display for EndPoint {
@@ -46,16 +55,58 @@ impl fmt::Display for EndPoint {
                 EntryReload(id) => format!("/api/entries/{}/reload.json", id),
                 EntryTags(id) => format!("/api/entries/{}/tags.json", id),
                 Export(id, format) => format!("/api/entries/{}/export.{}", id, format),
+                CategoryExport(category, format) => format!("/export/{}.{}", category, format),
                 Tag(i) => format!("/api/tags/{}.json", i),
                 Entry(i) => format!("/api/entries/{}.json", i),
+                Annotations(i) => format!("/api/annotations/{}.json", i),
                 Annotation(i) => format!("/api/annotations/{}.json", i),
             }
         )
     }
 }
 
+impl EndPoint {
+    /// A short name for the endpoint without any ids, for use as a label in logs and metrics.
+    pub(crate) fn name(&self) -> &'static str {
+        use self::EndPoint::*;
+        match self {
+            Token => "token",
+            Entries => "entries",
+            Exists => "exists",
+            Version => "version",
+            User => "user",
+            Tags => "tags",
+            TagsLabel => "tags_label",
+            TagLabel => "tag_label",
+            DeleteEntryTag(_, _) => "entry_tag",
+            EntryReload(_) => "entry_reload",
+            EntryTags(_) => "entry_tags",
+            Export(_, _) => "entry_export",
+            CategoryExport(_, _) => "category_export",
+            Tag(_) => "tag",
+            Entry(_) => "entry",
+            Annotations(_) => "annotations",
+            Annotation(_) => "annotation",
+        }
+    }
+
+    /// The id of the entry this endpoint operates on, if known.
+    pub(crate) fn entry_id(&self) -> Option<EntryId> {
+        use self::EndPoint::*;
+        match *self {
+            DeleteEntryTag(id, _)
+            | EntryReload(id)
+            | EntryTags(id)
+            | Export(id, _)
+            | Entry(id)
+            | Annotations(id) => Some(id),
+            _ => None,
+        }
+    }
+}
+
 /// Used by the API client to build URLs to send requests to.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct UrlBuilder {
     base_url: String,
 }
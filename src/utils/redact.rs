@@ -0,0 +1,86 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Helpers to keep secrets and article content out of logs and recordings.
+
+use serde_json::Value;
+
+/// Placeholder for a value that was removed.
+pub(crate) const REDACTED: &str = "REDACTED";
+
+/// Keys whose values are credentials.
+pub(crate) const SECRET_KEYS: &[&str] =
+    &["access_token", "refresh_token", "password", "client_secret"];
+
+/// Keys whose values are (possibly large or private) user content.
+const CONTENT_KEYS: &[&str] = &["content", "text", "quote"];
+
+/// Maximum number of bytes of a body that is logged.
+const MAX_LOGGED_BODY: usize = 1024;
+
+/// Replace the values of `keys` anywhere in a json document.
+pub(crate) fn redact_json(value: &mut Value, keys: &[&str]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if keys.contains(&key.as_str()) {
+                    *value = Value::String(REDACTED.to_owned());
+                } else {
+                    redact_json(value, keys);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact_json(value, keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Format a response body for logging. Credentials and content are redacted, and the result is
+/// truncated. Bodies that aren't json are not logged at all, since they can't be redacted.
+pub(crate) fn loggable_body(text: &str) -> String {
+    let mut json = match serde_json::from_str::<Value>(text) {
+        Ok(json) => json,
+        Err(_) => return format!("<{} bytes of non-json body>", text.len()),
+    };
+    redact_json(&mut json, SECRET_KEYS);
+    redact_json(&mut json, CONTENT_KEYS);
+    let mut redacted = json.to_string();
+
+    if redacted.len() > MAX_LOGGED_BODY {
+        let mut end = MAX_LOGGED_BODY;
+        while !redacted.is_char_boundary(end) {
+            end -= 1;
+        }
+        let total = redacted.len();
+        redacted.truncate(end);
+        redacted.push_str(&format!("... ({} bytes total)", total));
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loggable_body_redacts_nested_values() {
+        let body = r#"{"_embedded":{"items":[{"id":1,"content":"<p>secret article</p>"}]},"access_token":"abc"}"#;
+        assert_eq!(
+            loggable_body(body),
+            r#"{"_embedded":{"items":[{"content":"REDACTED","id":1}]},"access_token":"REDACTED"}"#
+        );
+    }
+
+    #[test]
+    fn test_loggable_body_is_bounded() {
+        let body = serde_json::to_string(&vec!["é"; 1000]).unwrap();
+        let logged = loggable_body(&body);
+        assert!(logged.len() < MAX_LOGGED_BODY + 30);
+        assert!(logged.ends_with(&format!("... ({} bytes total)", body.len())));
+        assert_eq!(loggable_body("plain text"), "<10 bytes of non-json body>");
+    }
+}
@@ -8,7 +8,9 @@
 use std::collections::HashMap;
 
 use serde::de::Error as DeError;
-use serde::{Deserialize, Deserializer, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::Authors;
 
 /// Used to serialize the boolean values to pseudo-bool integers. The api appears to support actual
 /// bool, but probably should follow the api docs just in case.
@@ -26,6 +28,20 @@ where
     }
 }
 
+/// Serialize authors as a list, like the server does in entries.
+pub(crate) fn authors_as_list<S>(
+    authors: &Option<Authors>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    authors
+        .as_ref()
+        .map(|authors| authors.names())
+        .serialize(serializer)
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
 enum IntBool {
@@ -0,0 +1,183 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Instrumentation of API calls. Spans are only emitted when the `tracing` feature is enabled.
+//!
+//! Every public method of `Client` has a span named after the method. Each HTTP request it sends
+//! gets a `wallabag_request` span inside it, so a paginated call has a request span per page,
+//! and a call answered from the cache has none.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Instant;
+
+use log::trace;
+use surf::http::Method;
+use surf::Response;
+
+use crate::errors::ClientResult;
+use crate::utils::EndPoint;
+
+/// Tracks a single HTTP request, including a retry after refreshing an expired token.
+pub(crate) struct ApiCall {
+    method: Method,
+    end_point: EndPoint,
+    start: Instant,
+    retries: AtomicU32,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl ApiCall {
+    pub(crate) fn start(method: Method, end_point: EndPoint) -> Self {
+        #[cfg(feature = "tracing")]
+        let span = {
+            let span = tracing::info_span!(
+                "wallabag_request",
+                endpoint = end_point.name(),
+                method = %method,
+                entry_id = tracing::field::Empty,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                retries = 0u32,
+            );
+            if let Some(id) = end_point.entry_id() {
+                span.record("entry_id", id.as_int());
+            }
+            span
+        };
+
+        Self {
+            method,
+            end_point,
+            start: Instant::now(),
+            retries: AtomicU32::new(0),
+            #[cfg(feature = "tracing")]
+            span,
+        }
+    }
+
+    /// Run the future making the requests for this call inside the span of the call.
+    pub(crate) async fn instrument<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+            future.instrument(self.span.clone()).await
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            future.await
+        }
+    }
+
+    /// Note that the request is sent again.
+    pub(crate) fn retry(&self) {
+        let retries = self.retries.fetch_add(1, Ordering::Relaxed) + 1;
+        #[cfg(feature = "tracing")]
+        self.span.record("retries", retries);
+        #[cfg(not(feature = "tracing"))]
+        let _ = retries;
+    }
+
+    /// Record the outcome of the call.
+    pub(crate) fn finish(&self, result: &ClientResult<Response>) {
+        let status = match result {
+            Ok(response) => Some(response.status()),
+            Err(e) => e.status(),
+        };
+        let latency = self.start.elapsed();
+        trace!(
+            "{} {} (entry {:?}) finished with status {:?} in {:?} after {} retries",
+            self.method,
+            self.end_point.name(),
+            self.end_point.entry_id().map(|id| id.as_int()),
+            status,
+            latency,
+            self.retries.load(Ordering::Relaxed)
+        );
+
+        #[cfg(feature = "tracing")]
+        {
+            if let Some(status) = status {
+                self.span.record("status", u16::from(status));
+            }
+            self.span.record("latency_ms", latency.as_millis() as u64);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_std::task::block_on;
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::cache::CacheConfig;
+    use crate::testing::MockServer;
+
+    /// Records the name of each span with the name of its parent.
+    #[derive(Default)]
+    struct Spans {
+        spans: Mutex<Vec<(&'static str, Option<&'static str>)>>,
+        entered: Mutex<Vec<u64>>,
+    }
+
+    impl Subscriber for Spans {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target().starts_with("wallabag_api")
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            let parent = if span.is_contextual() {
+                self.entered.lock().unwrap().last().copied()
+            } else {
+                span.parent().map(Id::into_u64)
+            };
+            let parent = parent.map(|id| spans[id as usize - 1].0);
+            spans.push((span.metadata().name(), parent));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            self.entered.lock().unwrap().push(span.into_u64());
+        }
+
+        fn exit(&self, _span: &Id) {
+            self.entered.lock().unwrap().pop();
+        }
+    }
+
+    #[test]
+    fn test_spans_per_method() {
+        let spans = std::sync::Arc::new(Spans::default());
+        tracing::subscriber::with_default(spans.clone(), || {
+            block_on(async {
+                let server = MockServer::new();
+                let mut client = server.client().with_cache(CacheConfig::default());
+                client.get_tags().await.unwrap();
+                client.get_tags().await.unwrap();
+            })
+        });
+
+        assert_eq!(
+            *spans.spans.lock().unwrap(),
+            vec![
+                ("get_tags", None),
+                ("wallabag_request", Some("get_tags")),
+                // The token is requested while sending the first request.
+                ("wallabag_request", Some("wallabag_request")),
+                // The second call is answered from the cache.
+                ("get_tags", None),
+            ]
+        );
+    }
+}
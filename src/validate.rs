@@ -0,0 +1,324 @@
+// Copyright 2026 Casper Meijn <casper@meijn.net>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that run before data is sent to the server.
+//!
+//! The client validates new entries, entry changes, annotations and tags before sending a
+//! request, and returns `ClientError::Validation` without contacting the server if they are
+//! invalid. The checks can also be run directly:
+//!
+//! ```
+//! use wallabag_api::errors::ValidationError;
+//! use wallabag_api::types::NewEntry;
+//! use wallabag_api::validate::Validate;
+//!
+//! let entry = NewEntry::new_with_url("ftp://example.com/file".to_owned());
+//! assert!(matches!(
+//!     entry.validate(),
+//!     Err(ValidationError::InvalidUrl { field: "url", .. })
+//! ));
+//! ```
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Utc};
+use surf::Url;
+
+use crate::errors::ValidationError;
+use crate::types::{Annotation, Authors, NewAnnotation, NewEntry, PatchEntry, Range};
+
+/// Data that can be checked before it is sent to the server.
+pub trait Validate {
+    /// Returns the first problem found, if any.
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+impl Validate for NewEntry {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_url("url", &self.url)?;
+        validate_optional_url("origin_url", self.origin_url.as_ref())?;
+        validate_optional_url("preview_picture", self.preview_picture.as_ref())?;
+        if let Some(ref tags) = self.tags {
+            validate_tags(tags)?;
+        }
+        if let Some(ref authors) = self.authors {
+            validate_authors(authors)?;
+        }
+        validate_published_at(self.published_at.as_ref())
+    }
+}
+
+impl Validate for PatchEntry {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_optional_url("origin_url", self.origin_url.as_ref())?;
+        validate_optional_url("preview_picture", self.preview_picture.as_ref())?;
+        if let Some(ref tags) = self.tags {
+            validate_tags(tags)?;
+        }
+        if let Some(authors) = self.authors.as_ref() {
+            validate_authors(authors)?;
+        }
+        validate_published_at(self.published_at.as_ref())
+    }
+}
+
+impl Validate for NewAnnotation {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.quote.trim().is_empty() {
+            return Err(ValidationError::EmptyQuote);
+        }
+        validate_ranges(&self.ranges)
+    }
+}
+
+impl Validate for Annotation {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(ref quote) = self.quote {
+            if quote.trim().is_empty() {
+                return Err(ValidationError::EmptyQuote);
+            }
+        }
+        validate_ranges(&self.ranges)
+    }
+}
+
+/// Check a single range. The index in the error is always 0.
+impl Validate for Range {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_range(0, self)
+    }
+}
+
+fn validate_ranges(ranges: &[Range]) -> Result<(), ValidationError> {
+    if ranges.is_empty() {
+        return Err(ValidationError::NoRanges);
+    }
+    ranges
+        .iter()
+        .enumerate()
+        .try_for_each(|(index, range)| validate_range(index, range))
+}
+
+/// Check that the range isn't reversed. The order of two paths can only be told when they
+/// first differ in the index of elements with the same name, like `/p[2]` and `/p[3]`. Other
+/// ranges across different paths, eg. from `/div[1]` to `/p[1]`, are not checked.
+fn validate_range(index: usize, range: &Range) -> Result<(), ValidationError> {
+    let invalid = |reason| Err(ValidationError::InvalidRange { index, reason });
+    match (&range.start, &range.end) {
+        (Some(start), Some(end)) => {
+            if start.is_empty() || end.is_empty() {
+                return invalid("start and end must not be empty");
+            }
+            match path_order(start, end) {
+                Some(Ordering::Greater) => invalid("end is before start"),
+                Some(Ordering::Equal) if range.end_offset < range.start_offset => {
+                    invalid("end offset is before start offset")
+                }
+                _ => Ok(()),
+            }
+        }
+        _ => invalid("start and end are required"),
+    }
+}
+
+/// The document order of two XPaths like `/div[1]/p[2]`, if it can be told from the paths alone.
+fn path_order(start: &str, end: &str) -> Option<Ordering> {
+    fn steps(path: &str) -> impl Iterator<Item = (String, Option<u32>)> + '_ {
+        path.split('/').filter(|step| !step.is_empty()).map(|step| {
+            match step.strip_suffix(']').and_then(|step| step.split_once('[')) {
+                Some((name, index)) => (name.to_lowercase(), index.parse().ok()),
+                None => (step.to_lowercase(), Some(1)),
+            }
+        })
+    }
+
+    let mut start = steps(start);
+    let mut end = steps(end);
+    loop {
+        match (start.next(), end.next()) {
+            (None, None) => return Some(Ordering::Equal),
+            // One is an ancestor of the other, the offsets are in different elements.
+            (None, Some(_)) | (Some(_), None) => return None,
+            (Some(start), Some(end)) if start == end => {}
+            (Some((start_name, Some(start))), Some((end_name, Some(end))))
+                if start_name == end_name =>
+            {
+                return Some(start.cmp(&end))
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Check that tag labels can be sent to the server. Labels containing a comma would be split
+/// into multiple tags by some endpoints.
+pub fn validate_tags<T: AsRef<str>>(labels: &[T]) -> Result<(), ValidationError> {
+    match labels.iter().find(|label| label.as_ref().contains(',')) {
+        Some(label) => Err(ValidationError::TagContainsComma {
+            label: label.as_ref().to_owned(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Authors are sent as a comma separated string, so a name containing a comma would be split.
+fn validate_authors(authors: &Authors) -> Result<(), ValidationError> {
+    match authors.names().iter().find(|name| name.contains(',')) {
+        Some(name) => Err(ValidationError::AuthorContainsComma { name: name.clone() }),
+        None => Ok(()),
+    }
+}
+
+fn validate_url(field: &'static str, url: &str) -> Result<(), ValidationError> {
+    match Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(()),
+        _ => Err(ValidationError::InvalidUrl {
+            field,
+            url: url.to_owned(),
+        }),
+    }
+}
+
+fn validate_optional_url(field: &'static str, url: Option<&String>) -> Result<(), ValidationError> {
+    match url {
+        Some(url) => validate_url(field, url),
+        None => Ok(()),
+    }
+}
+
+fn validate_published_at(published_at: Option<&DateTime<Utc>>) -> Result<(), ValidationError> {
+    match published_at {
+        Some(&published_at) if published_at > Utc::now() => {
+            Err(ValidationError::PublishedInFuture { published_at })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use crate::ClientError;
+    use async_std::task::block_on;
+    use chrono::Duration;
+
+    fn range(start: &str, start_offset: u32, end_offset: u32) -> Range {
+        Range {
+            start: Some(start.to_owned()),
+            end: Some(start.to_owned()),
+            start_offset,
+            end_offset,
+        }
+    }
+
+    #[test]
+    fn test_validate_new_entry() {
+        let mut entry = NewEntry::new_with_url("https://example.com/article".to_owned());
+        assert_eq!(entry.validate(), Ok(()));
+
+        entry.tags = Some(vec!["rust".to_owned(), "a,b".to_owned()]);
+        assert_eq!(
+            entry.validate(),
+            Err(ValidationError::TagContainsComma {
+                label: "a,b".to_owned()
+            })
+        );
+
+        entry.tags = None;
+        let future = Utc::now() + Duration::days(1);
+        entry.published_at = Some(future);
+        assert_eq!(
+            entry.validate(),
+            Err(ValidationError::PublishedInFuture {
+                published_at: future
+            })
+        );
+
+        entry.published_at = None;
+        entry.authors = Some(Authors::new(vec!["Doe, John"]));
+        assert_eq!(
+            entry.validate(),
+            Err(ValidationError::AuthorContainsComma {
+                name: "Doe, John".to_owned()
+            })
+        );
+
+        let patch = PatchEntry {
+            origin_url: Some("javascript:alert(1)".to_owned()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            patch.validate(),
+            Err(ValidationError::InvalidUrl {
+                field: "origin_url",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_validate_annotation() {
+        let mut annotation = NewAnnotation {
+            quote: "quoted text".to_owned(),
+            ranges: vec![range("/p[1]", 0, 11)],
+            text: String::new(),
+        };
+        assert_eq!(annotation.validate(), Ok(()));
+
+        annotation.ranges.push(range("/p[2]", 5, 2));
+        assert_eq!(
+            annotation.validate(),
+            Err(ValidationError::InvalidRange {
+                index: 1,
+                reason: "end offset is before start offset"
+            })
+        );
+
+        annotation.ranges[1] = Range {
+            end: Some("/div[1]/P[1]".to_owned()),
+            ..range("/div[1]/p[2]", 0, 5)
+        };
+        assert_eq!(
+            annotation.validate(),
+            Err(ValidationError::InvalidRange {
+                index: 1,
+                reason: "end is before start"
+            })
+        );
+
+        // The order of different elements, or of an element and its descendant, is unknown.
+        annotation.ranges[1].end = Some("/div[1]/ul[1]".to_owned());
+        assert_eq!(annotation.validate(), Ok(()));
+        annotation.ranges[1].end = Some("/div[1]".to_owned());
+        assert_eq!(annotation.validate(), Ok(()));
+        annotation.ranges[1] = range("/div[1]/p", 5, 2);
+        annotation.ranges[1].start = Some("/div/p[1]".to_owned());
+        assert!(annotation.validate().is_err());
+
+        annotation.ranges.clear();
+        assert_eq!(annotation.validate(), Err(ValidationError::NoRanges));
+
+        annotation.quote = " ".to_owned();
+        assert_eq!(annotation.validate(), Err(ValidationError::EmptyQuote));
+    }
+
+    #[test]
+    fn test_client_validates_before_sending() {
+        block_on(async {
+            let server = MockServer::new();
+            let mut client = server.client();
+
+            let entry = NewEntry::new_with_url("not a url".to_owned());
+            let err = client.create_entry(&entry).await.unwrap_err();
+            assert!(matches!(
+                err,
+                ClientError::Validation(ValidationError::InvalidUrl { .. })
+            ));
+
+            let err = client.add_tags_to_entry(1, vec!["a,b"]).await.unwrap_err();
+            assert!(matches!(err, ClientError::Validation(_)));
+            assert!(server.requests().is_empty());
+        })
+    }
+}